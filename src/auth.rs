@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crypto::pbkdf2::{pbkdf2_simple, pbkdf2_check};
+
+// PBKDF2-HMAC-SHA256 iterations for new password hashes. Existing hashes
+// keep the count they were made with.
+const ROUNDS: u32 = 10000;
+
+pub trait Authenticator: Send + Sync + 'static {
+    fn authenticate(&self, login: &str, password: &str) -> bool;
+}
+
+pub struct AllowAnyone;
+
+impl Authenticator for AllowAnyone {
+    fn authenticate(&self, _login: &str, _password: &str) -> bool {
+        true
+    }
+}
+
+// One `login:hash` entry per line, where hash is salted PBKDF2 in the
+// format of `hash_password`.
+pub struct PasswordFile {
+    users: HashMap<String, String>,
+}
+
+impl PasswordFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<PasswordFile> {
+        let file = try!(File::open(path));
+        let mut users = HashMap::new();
+        for (n, line) in BufReader::new(file).lines().enumerate() {
+            let line = try!(line);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+
+            let parts = line.split(':').collect::<Vec<_>>();
+            if parts.len() != 2 || parts[0].is_empty() || !parts[1].starts_with("$rpbkdf2$") {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("Malformed password file line {}", n + 1)));
+            }
+            users.insert(parts[0].to_string(), parts[1].to_string());
+        }
+
+        Ok(PasswordFile { users: users })
+    }
+}
+
+impl Authenticator for PasswordFile {
+    fn authenticate(&self, login: &str, password: &str) -> bool {
+        match self.users.get(login) {
+            Some(hash) => pbkdf2_check(password, hash).unwrap_or(false),
+            None => false
+        }
+    }
+}
+
+// A hash for the password file, with a random salt.
+pub fn hash_password(password: &str) -> io::Result<String> {
+    pbkdf2_simple(password, ROUNDS)
+}
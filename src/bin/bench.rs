@@ -10,15 +10,17 @@ extern crate protobuf;
 use std::net;
 use std::io::{Write, Read};
 use std::str::FromStr;
+use std::thread;
 
-use chat::post::Post;
+use chat::post::{Post, Message_Type};
 use chat::proto_reader::ProtoReader;
+use chat::codec::Codec;
 
 const USAGE: &'static str = "
 bench
 
 Usage:
-  bench [--rps --packed --med --large --huge --c10k]
+  bench [--rps --packed --med --large --huge --c10k --order]
   chat (-h | --help)
 
 Options:
@@ -28,6 +30,7 @@ Options:
   --large        Megabytes per second benchmark. 2.7mb message, single connection.
   --huge         Megabytes per second benchmark. 800mb message, single connection.
   --c10k         10k concurrent connections benchmark. Four threads.
+  --order        Flood a client that does not read and check it gets messages in order.
   -h, --help     Show this screen.
 ";
 
@@ -40,6 +43,7 @@ struct Args {
     flag_large: bool,
     flag_huge: bool,
     flag_c10k: bool,
+    flag_order: bool,
 }
 
 
@@ -72,9 +76,54 @@ fn main() {
         println!("\n\nc10k benchmark");
         c10k();
     }
+    if args.flag_order {
+        println!("\n\norder check");
+        order();
+    }
     println!("\n\nBenchmarks finished");
 }
 
+// The reader buffers what it reads past the current message, so it has to
+// live as long as the socket.
+struct Conn {
+    sock: net::TcpStream,
+    reader: ProtoReader<Post>,
+}
+
+impl Conn {
+    fn write_all(&mut self, bytes: &[u8]) {
+        self.sock.write_all(bytes).unwrap();
+    }
+
+    // Answers pings on the way, so that slow benchmarks are not disconnected,
+    // and skips server notices such as "X joined", which are not what the
+    // benchmarks wait for.
+    fn read_post(&mut self) -> Post {
+        loop {
+            let post = self.reader.read(&mut self.sock).unwrap();
+            match post.get_field_type() {
+                Message_Type::PING => self.write_all(&Post::pong().to_bytes()),
+                Message_Type::MESSAGE if post.get_author().is_empty() => {}
+                _ => return post,
+            }
+        }
+    }
+}
+
+fn connect(addr: &net::SocketAddr) -> Conn {
+    connect_as(addr, "matklad")
+}
+
+fn connect_as(addr: &net::SocketAddr, login: &str) -> Conn {
+    let mut sock = Conn {
+        sock: net::TcpStream::connect(addr).unwrap(),
+        reader: ProtoReader::new(),
+    };
+    sock.write_all(&Post::login(login.to_string(), None).to_bytes());
+    while sock.read_post().get_field_type() != Message_Type::LOGIN {}
+    sock
+}
+
 fn message(message_size: usize) -> Post {
     let text = std::iter::repeat("Hello, World!").take(message_size)
     .collect::<Vec<_>>()
@@ -85,7 +134,10 @@ fn message(message_size: usize) -> Post {
 fn c10k() {
     let addr: net::SocketAddr = FromStr::from_str("0.0.0.0:20053").unwrap();
     let mut socks = Vec::new();
-    let message = message(1).to_bytes();
+    // Without an author, so that the server signs it with each sender's login.
+    let mut message = message(1);
+    message.clear_author();
+    let message = message.to_bytes();
     let start = time::precise_time_s();
     let mut pool = simple_parallel::Pool::new(4);
     for n_cons in 0..10_000 {
@@ -93,14 +145,15 @@ fn c10k() {
             println!("{} concurrent connections, {:.2} seconds",
                      n_cons, time::precise_time_s() - start);
         }
-        let mut sock = net::TcpStream::connect(&addr).unwrap();
+        // Logins are unique, each connection needs its own.
+        let mut sock = connect_as(&addr, &format!("c10k-{}", n_cons));
         if n_cons + 1 == 10_000 {
             println!("c10k!");
         }
-        sock.write_all(&message).unwrap();
+        sock.write_all(&message);
         socks.push(sock);
-        pool.for_(socks.iter_mut(), |mut sock| {
-            sock_read_post(&mut sock);
+        pool.for_(socks.iter_mut(), |sock| {
+            sock.read_post();
         });
     }
 
@@ -115,27 +168,27 @@ fn packed() {
     let addr: net::SocketAddr = FromStr::from_str("0.0.0.0:20053").unwrap();
     let n_requests = 100_000;
     let pack = 4;
-    let mut sock = net::TcpStream::connect(&addr).unwrap();
+    let mut sock = connect(&addr);
 
     let mut bytes_writen = 0;
     let start = time::precise_time_s();
-    let message = message(1).to_bytes();
-    let message_len = message.len();
-    println!("message len {} bytes", message_len);
-
+    let message = message(1);
+    let codec = Codec::new();
     let buffer = {
-        let mut v = Vec::with_capacity(message_len * 4);
+        let mut v = Vec::new();
         for _ in 0..pack {
-            v.extend(message.iter());
+            codec.encode_into(&message, &mut v);
         }
         v
     };
+    println!("message len {} bytes", buffer.len() / pack);
+
     for _ in 0..n_requests / pack {
         bytes_writen += buffer.len();
-        sock.write_all(&buffer).unwrap();
+        sock.write_all(&buffer);
 
         for _ in 0..pack {
-            sock_read_post(&mut sock);
+            sock.read_post();
         }
 
     }
@@ -158,7 +211,7 @@ fn huge()  { requests(5      , 60000000)  }
 fn requests(n_requests: u32, message_size: usize) {
     let addr: net::SocketAddr = FromStr::from_str("0.0.0.0:20053").unwrap();
 
-    let mut sock = net::TcpStream::connect(&addr).unwrap();
+    let mut sock = connect(&addr);
 
     let message = {
         let m = message(message_size);
@@ -178,8 +231,8 @@ fn requests(n_requests: u32, message_size: usize) {
     }
     for _ in 0..n_requests {
         bytes_writen += message.len();
-        sock.write_all(&message).unwrap();
-        sock_read_post(&mut sock);
+        sock.write_all(&message);
+        sock.read_post();
     }
 
     let end = time::precise_time_s();
@@ -192,8 +245,41 @@ fn requests(n_requests: u32, message_size: usize) {
 }
 
 
-fn sock_read_post(mut sock: &mut net::TcpStream) -> Post {
-    let mut reader = ProtoReader::<Post>::new();
-    let msg = reader.read(&mut sock).unwrap();
-    msg
+// The slow client sits on a full socket while the server queues thousands of
+// messages for it. Messages may be dropped, depending on the server overflow
+// policy, but those that arrive must be in the order they were sent.
+fn order() {
+    let addr: net::SocketAddr = FromStr::from_str("0.0.0.0:20053").unwrap();
+    let n_messages = 20_000;
+    let padding = std::iter::repeat("x").take(1000).collect::<String>();
+
+    let mut slow = connect_as(&addr, "slow");
+    let mut fast = connect_as(&addr, "fast");
+    let mut fast_reader = fast.sock.try_clone().unwrap();
+    thread::spawn(move || {
+        let mut reader = ProtoReader::<Post>::new();
+        while reader.read(&mut fast_reader).is_ok() {}
+    });
+
+    let start = time::precise_time_s();
+    for i in 0..n_messages {
+        let post = Post::from_text("fast".to_string(), vec![format!("{} {}", i, padding)]);
+        fast.write_all(&post.to_bytes());
+    }
+    println!("sent {} messages in {:.2} seconds", n_messages, time::precise_time_s() - start);
+
+    let mut expected = 0;
+    let mut received = 0;
+    while expected < n_messages {
+        let post = slow.read_post();
+        if post.get_author() != "fast" {
+            continue;
+        }
+        let n = post.get_text()[0].split(' ').next().unwrap().parse::<usize>().unwrap();
+        assert!(n >= expected, "got message {} after {}", n, expected - 1);
+        expected = n + 1;
+        received += 1;
+    }
+    println!("received {} of {} messages in order", received, n_messages);
 }
+
@@ -3,13 +3,17 @@ extern crate docopt;
 extern crate byteorder;
 extern crate chat;
 extern crate protobuf;
+extern crate time;
 
 use std::net;
 use std::io::{self, BufRead, Write, Read};
 use std::str::FromStr;
+use std::cmp::min;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
-use chat::post::Post;
+use chat::post::{Post, Message_Type};
 use chat::proto_reader::ProtoReader;
 
 
@@ -17,20 +21,31 @@ const USAGE: &'static str = "
 auchat
 
 Usage:
-  auchat [--addr=<host:port>] [--login=<login>]
+  auchat [--addr=<host:port>] [--login=<login>] [--password=<password>] [--history=<n>]
   chat (-h | --help)
 
 Options:
   --addr=<host:port>          Port to listen [default: 0.0.0.0:20053]
   --login=<login>             Login [default: anonymous]
+  --password=<password>       Password, if the server requires one.
+  --history=<n>               Number of recent messages to show after login.
   -h, --help                  Show this screen.
 ";
 
+// After this much silence from the server the client pings it, and after as
+// much again gives up on it.
+const PING_INTERVAL_SECS: u64 = 30;
+
+// Delay before reconnecting, doubled after each failed attempt.
+const MIN_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30000;
 
 #[derive(Debug, RustcDecodable)]
 struct Args {
     flag_addr: String,
     flag_login: String,
+    flag_password: Option<String>,
+    flag_history: Option<u32>,
 }
 
 
@@ -39,64 +54,196 @@ fn main() {
     .and_then(|d| d.options_first(true).decode())
     .unwrap_or_else(|e| e.exit());
 
-    let Args {flag_addr: addr, flag_login: login} = args;
+    let Args {flag_addr: addr, flag_login: login, flag_password: password, flag_history: history} = args;
     let addr: net::SocketAddr = FromStr::from_str(&addr)
     .ok().expect(&format!("Failed to parse host:port string: {}", addr));
 
-    let sock = match net::TcpStream::connect(&addr) {
-        Ok(sock) => sock,
-        Err(e) => {
-            println!("Failed to connect to {}: {}", addr, e);
-            return;
-        }
-    };
+    let session = Arc::new(Session {
+        out: Mutex::new(None),
+        room: Mutex::new(None),
+    });
+    {
+        let session = session.clone();
+        thread::spawn(move || writer(&session));
+    }
 
-    let sock2 = match sock.try_clone() {
-        Ok(sock) => sock,
-        Err(e) => {
-            println!("Cant write and read socket simulaneously: {}", e);
-            return;
+    let mut backoff = MIN_BACKOFF_MS;
+    let mut last_id = None;
+    loop {
+        println!("Connecting to {}", addr);
+        match connect(&addr, &session, login.clone(), password.clone(), last_id, history) {
+            Ok(sock) => match reader(sock, &session, &mut last_id) {
+                // The server did not let us in, trying again will not help.
+                Disconnect::Rejected => return,
+                Disconnect::Lost { logged_in } => if logged_in {
+                    backoff = MIN_BACKOFF_MS;
+                }
+            },
+            Err(e) => println!("Failed to connect to {}: {}", addr, e),
         }
-    };
+        *session.out.lock().unwrap() = None;
 
-    println!("Connecting to {}", addr);
-    thread::spawn(move || {
-        writer(sock2, login)
-    });
+        println!("Reconnecting in {} ms", backoff);
+        thread::sleep(Duration::from_millis(backoff));
+        backoff = min(backoff * 2, MAX_BACKOFF_MS);
+    }
+}
 
-    thread::spawn(move || {
-        reader(sock)
-    }).join().unwrap();;
+// What survives reconnects. The writer thread sends what the user types to
+// `out`, which is `None` while there is no connection.
+struct Session {
+    out: Mutex<Option<net::TcpStream>>,
+    room: Mutex<Option<String>>,
+}
 
+enum Disconnect {
+    Rejected,
+    Lost { logged_in: bool },
 }
 
-fn sock_read_post(mut sock: &mut net::TcpStream) -> Post {
-    let mut reader = ProtoReader::<Post>::new();
-    let msg = reader.read(&mut sock).unwrap();
-    msg
+// Logs in, asking for the messages missed since `last_id` if there was an
+// earlier connection, and rejoins the room the user was in.
+fn connect(addr: &net::SocketAddr,
+           session: &Session,
+           login: String,
+           password: Option<String>,
+           last_id: Option<u64>,
+           history: Option<u32>) -> io::Result<net::TcpStream> {
+    let sock = try!(net::TcpStream::connect(addr));
+    try!(sock.set_read_timeout(Some(Duration::from_secs(PING_INTERVAL_SECS))));
+    let out = try!(sock.try_clone());
+
+    let mut login_post = Post::login(login, password);
+    match (last_id, history) {
+        (Some(id), _) => login_post.set_since(id),
+        (None, Some(limit)) => login_post.set_limit(limit),
+        (None, None) => {}
+    }
+    let mut out = Some(out);
+    try!(write_message(&mut out, &login_post));
+    if let Some(ref room) = *session.room.lock().unwrap() {
+        try!(write_message(&mut out, &Post::join_room(room.clone())));
+    }
+    *session.out.lock().unwrap() = out;
+    Ok(sock)
 }
 
-fn reader(mut sock: net::TcpStream) {
+fn reader(mut sock: net::TcpStream, session: &Session, last_id: &mut Option<u64>) -> Disconnect {
+    let mut reader = ProtoReader::<Post>::new();
+    let mut pinged = false;
+    let mut logged_in = false;
+    // The server is full or busy, unlike a bad login this may pass.
+    let mut refused = false;
     loop {
-        let (author, lines) = sock_read_post(&mut sock).take();
-        println!("{}: {}", author, lines.join("\n"));
+        let post = match reader.read_next(&mut sock) {
+            Ok(Some(post)) => post,
+            Ok(None) => {
+                println!("Server closed the connection");
+                return if logged_in || refused {
+                    Disconnect::Lost { logged_in: logged_in }
+                } else {
+                    Disconnect::Rejected
+                };
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+                if pinged {
+                    println!("Server is not responding");
+                    return Disconnect::Lost { logged_in: logged_in };
+                }
+                pinged = true;
+                if let Err(e) = write_message(&mut session.out.lock().unwrap(), &Post::ping()) {
+                    println!("Failed to ping server: {}", e);
+                    return Disconnect::Lost { logged_in: logged_in };
+                }
+                continue;
+            }
+            Err(e) => {
+                println!("Failed to read from server: {}", e);
+                return Disconnect::Lost { logged_in: logged_in };
+            }
+        };
+        pinged = false;
+        match post.get_field_type() {
+            Message_Type::PING => {
+                if let Err(e) = write_message(&mut session.out.lock().unwrap(), &Post::pong()) {
+                    println!("Failed to answer server ping: {}", e);
+                    return Disconnect::Lost { logged_in: logged_in };
+                }
+                continue;
+            }
+            Message_Type::PONG => continue,
+            Message_Type::LOGIN => logged_in = true,
+            Message_Type::REJECTED => refused = true,
+            _ => {}
+        }
+        // Only messages to everyone are kept in the server history, so
+        // these are the ones to resume from.
+        if post.has_id() && post.room().is_none() && !post.has_recipient() {
+            *last_id = Some(post.get_id());
+        }
+
+        let at = if post.has_timestamp() {
+            let ms = post.get_timestamp();
+            let tm = time::at(time::Timespec::new(ms / 1000, (ms % 1000 * 1000000) as i32));
+            format!("{} ", time::strftime("%H:%M:%S", &tm).unwrap())
+        } else {
+            String::new()
+        };
+        let room = post.room().map_or(String::new(), |room| format!("[{}] ", room));
+        let recipient = if post.has_recipient() {
+            format!(" -> {}", post.get_recipient())
+        } else {
+            String::new()
+        };
+        let (author, lines) = post.take();
+        println!("{}{}{}{}: {}", at, room, author, recipient, lines.join("\n"));
     }
 }
 
-fn writer(mut sock: net::TcpStream, login: String) {
+fn writer(session: &Session) {
     let stdin = io::stdin();
     for line in stdin.lock().lines() {
         match line {
             Ok(line) => {
-                let message = if line.starts_with("/") {
+                let message = if line.starts_with("/join ") {
+                    let name = line["/join ".len()..].trim().to_string();
+                    *session.room.lock().unwrap() = Some(name.clone());
+                    Post::join_room(name)
+                } else if line.starts_with("/leave") {
+                    match session.room.lock().unwrap().take() {
+                        Some(name) => Post::leave_room(name),
+                        None => {
+                            println!("You are not in a room");
+                            continue;
+                        }
+                    }
+                } else if line == "/rooms" {
+                    Post::list_rooms()
+                } else if line == "/who" {
+                    Post::who()
+                } else if line.starts_with("/history") {
+                    let limit = line["/history".len()..].trim().parse().ok();
+                    Post::history(None, limit.or(Some(20)))
+                } else if line.starts_with("/") {
                     Post::from_command(line[1..].to_string())
+                } else if line.starts_with("@") {
+                    let mut parts = line[1..].splitn(2, ' ');
+                    let recipient = parts.next().unwrap_or("").to_string();
+                    let text = parts.next().unwrap_or("").to_string();
+                    let mut post = Post::from_text(String::new(), vec![text]);
+                    post.set_recipient(recipient);
+                    post
                 } else {
-                    Post::from_text(login.clone(), vec![line])
+                    let mut post = Post::from_text(String::new(), vec![line]);
+                    if let Some(ref name) = *session.room.lock().unwrap() {
+                        post.set_room(name.clone());
+                    }
+                    post
                 };
 
-                if let Err(e) = write_message(&mut sock, &message) {
+                // A failed write is noticed by the reader, which reconnects.
+                if let Err(e) = write_message(&mut session.out.lock().unwrap(), &message) {
                     println!("Failed to deliver the message: {}", e);
-                    break;
                 }
             },
             Err(e) => println!("Error reading line: {}", e),
@@ -104,8 +251,10 @@ fn writer(mut sock: net::TcpStream, login: String) {
     }
 }
 
-fn write_message(sock: &mut net::TcpStream, msg: &Post) -> io::Result<()> {
-    try!(sock.write_all(&msg.to_bytes()));
-    Ok(())
+fn write_message(sock: &mut Option<net::TcpStream>, msg: &Post) -> io::Result<()> {
+    match *sock {
+        Some(ref mut sock) => sock.write_all(&msg.to_bytes()),
+        None => Err(io::Error::new(io::ErrorKind::NotConnected, "not connected to the server")),
+    }
 }
 
@@ -2,46 +2,240 @@
 extern crate env_logger;
 extern crate rustc_serialize;
 extern crate docopt;
+extern crate chan_signal;
 extern crate chat;
 
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::cmp::max;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chan_signal::Signal;
+
+use chat::auth::{self, Authenticator, AllowAnyone, PasswordFile};
+use chat::history::History;
+use chat::shell::{self, AllowList};
+use chat::commands::{Commands, Visibility};
+use chat::{SendLimit, Overflow, Timeouts, ConnectionLimits, Metrics};
 
 const USAGE: &'static str = "
 Mio chat
 
 Usage:
-  chat [--workers=<n_workers> --addr=<host:port>]
+  chat [options]
+  chat --hash-password=<password>
   chat (-h | --help)
 
 Options:
   -w, --workers=<n_workers>   Number of worker threads.
   --addr=<host:port>          Port to listen [default: 0.0.0.0:20053]
+  --auth=<backend>            Authentication backend, anyone or file [default: anyone]
+  --passwd=<file>             Password file for the file backend [default: passwd]
+  --hash-password=<password>  Print the hash of the password for the password file and exit.
+  --history=<file>            File to keep message history in [default: history.log]
+  --commands=<file>           Shell commands users may run. If absent, none are allowed.
+  --shell-dir=<dir>           Working directory for shell commands [default: .]
+  --shell-timeout=<ms>        Kill shell commands running longer than this [default: 5000]
+  --shell-output=<bytes>      Cut stdout and stderr of shell commands to this size [default: 65536]
+  --shell-threads=<n>         Number of threads running shell commands [default: 4]
+  --shell-queue=<n>           Number of shell commands waiting for a thread [default: 64]
+  --shell-per-user=<n>        Number of shell commands a single user may have in flight [default: 2]
+  --visibility=<who>          Who sees commands and their results, private or public [default: private]
+  --send-limit=<bytes>        Bytes queued for a single client before it is considered slow [default: 1073741824]
+  --overflow=<policy>         What to do with slow clients: dropoldest, disconnect or pause [default: pause]
+  --max-message=<bytes>       Disconnect clients sending larger messages [default: 1073741824]
+  --idle-timeout=<ms>         Disconnect clients silent for this long, 0 to disable [default: 0]
+  --frame-timeout=<ms>        Disconnect clients taking longer to send a message [default: 30000]
+  --login-timeout=<ms>        Disconnect clients not logged in this long after connecting [default: 10000]
+  --ping-interval=<ms>        Ping clients silent for this long, 0 to disable [default: 60000]
+  --ping-timeout=<ms>         Disconnect pinged clients not answering in time [default: 20000]
+  --max-connections=<n>       Refuse clients beyond this many, 0 for no limit [default: 100000]
+  --max-per-ip=<n>            Refuse clients beyond this many from one address, 0 for no limit [default: 0]
+  --accept-rate=<n>           Refuse clients connecting more often per second from one address, 0 for no limit [default: 0]
+  --stats=<secs>              Log dropped message counts this often, 0 to disable [default: 60]
+  --shutdown-timeout=<ms>     Time clients get to receive pending messages on SIGINT or SIGTERM [default: 5000]
   -h, --help                  Show this screen.
 ";
 
+#[derive(Debug, RustcDecodable)]
+enum Auth {
+    Anyone,
+    File,
+}
+
+#[derive(Debug, RustcDecodable)]
+enum Who {
+    Private,
+    Public,
+}
+
+#[derive(Debug, RustcDecodable)]
+enum Policy {
+    DropOldest,
+    Disconnect,
+    Pause,
+}
+
 #[derive(Debug, RustcDecodable)]
 struct Args {
     flag_workers: usize,
-    flag_addr: String
+    flag_addr: String,
+    flag_auth: Auth,
+    flag_passwd: String,
+    flag_hash_password: Option<String>,
+    flag_history: String,
+    flag_commands: Option<String>,
+    flag_shell_dir: String,
+    flag_shell_timeout: u64,
+    flag_shell_output: usize,
+    flag_shell_threads: usize,
+    flag_shell_queue: usize,
+    flag_shell_per_user: usize,
+    flag_visibility: Who,
+    flag_send_limit: usize,
+    flag_overflow: Policy,
+    flag_max_message: usize,
+    flag_idle_timeout: u64,
+    flag_frame_timeout: u64,
+    flag_login_timeout: u64,
+    flag_ping_interval: u64,
+    flag_ping_timeout: u64,
+    flag_max_connections: usize,
+    flag_max_per_ip: usize,
+    flag_accept_rate: f64,
+    flag_stats: u64,
+    flag_shutdown_timeout: u64,
 }
 
 
 pub fn main() {
+    // Must come before any thread is spawned, so that they all inherit the signal mask.
+    let signal = chan_signal::notify(&[Signal::INT, Signal::TERM]);
     env_logger::init().ok().expect("Failed to init logger");
 
     let args: Args = docopt::Docopt::new(USAGE)
         .and_then(|d| d.options_first(true).decode())
         .unwrap_or_else(|e| e.exit());
 
+    if let Some(password) = args.flag_hash_password {
+        match auth::hash_password(&password) {
+            Ok(hash) => println!("{}", hash),
+            Err(e) => error!("Failed to hash the password: {}", e),
+        }
+        return;
+    }
+
     let n_workers = max(args.flag_workers, 1);
     let addr = args.flag_addr;
 
     let addr: SocketAddr = FromStr::from_str(&addr)
         .ok().expect("Failed to parse host:port string");
 
-    chat::start_server(addr, n_workers);
+    let auth: Arc<Authenticator> = match args.flag_auth {
+        Auth::Anyone => Arc::new(AllowAnyone),
+        Auth::File => match PasswordFile::open(&args.flag_passwd) {
+            Ok(passwd) => Arc::new(passwd),
+            Err(e) => {
+                error!("Failed to read password file {}: {}", args.flag_passwd, e);
+                return;
+            }
+        }
+    };
+
+    let history = match History::open(&args.flag_history) {
+        Ok(history) => history,
+        Err(e) => {
+            error!("Failed to open history file {}: {}", args.flag_history, e);
+            return;
+        }
+    };
+
+    let commands = match args.flag_commands {
+        None => AllowList::empty(),
+        Some(ref path) => match AllowList::open(path) {
+            Ok(commands) => commands,
+            Err(e) => {
+                error!("Failed to read command file {}: {}", path, e);
+                return;
+            }
+        }
+    };
+    let shell = shell::Config {
+        commands: commands,
+        dir: args.flag_shell_dir.into(),
+        timeout_ms: args.flag_shell_timeout,
+        max_output: args.flag_shell_output,
+        n_threads: max(args.flag_shell_threads, 1),
+        queue_len: args.flag_shell_queue,
+        max_per_user: max(args.flag_shell_per_user, 1),
+    };
+
+    let mut commands = Commands::new();
+    commands.set_visibility(match args.flag_visibility {
+        Who::Private => Visibility::Private,
+        Who::Public => Visibility::Public,
+    });
+
+    let limit = SendLimit {
+        max_bytes: args.flag_send_limit,
+        overflow: match args.flag_overflow {
+            Policy::DropOldest => Overflow::DropOldest,
+            Policy::Disconnect => Overflow::Disconnect,
+            Policy::Pause => Overflow::Pause,
+        },
+    };
+    let metrics = Arc::new(Metrics::new());
+    if args.flag_stats > 0 {
+        let metrics = metrics.clone();
+        let interval = Duration::from_secs(args.flag_stats);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            info!("dropped {} messages ({} bytes), disconnected {} and paused {} slow clients, \
+                   refused {} connections",
+                  metrics.dropped_messages(), metrics.dropped_bytes(),
+                  metrics.overflow_disconnects(), metrics.pauses(),
+                  metrics.rejected_connections());
+        });
+    }
+
+    let server = chat::Builder::new(addr)
+        .history(history)
+        .workers(n_workers)
+        .auth(auth)
+        .shell(shell)
+        .commands(commands)
+        .send_limit(limit)
+        .max_message(args.flag_max_message)
+        .timeouts(Timeouts {
+            idle_ms: args.flag_idle_timeout,
+            frame_ms: args.flag_frame_timeout,
+            handshake_ms: args.flag_login_timeout,
+            ping_ms: args.flag_ping_interval,
+            pong_ms: args.flag_ping_timeout,
+        })
+        .connection_limits(ConnectionLimits {
+            max_connections: args.flag_max_connections,
+            max_per_ip: args.flag_max_per_ip,
+            accept_rate: args.flag_accept_rate,
+        })
+        .metrics(metrics)
+        .start();
+    let server = match server {
+        Ok(server) => server,
+        Err(e) => {
+            error!("{}", e);
+            return;
+        }
+    };
+    println!("Starting server at {}", server.local_addr());
+
+    if let Some(signal) = signal.recv() {
+        info!("Received {:?}", signal);
+    }
+    server.stop(args.flag_shutdown_timeout);
+    server.join();
 }
 
 
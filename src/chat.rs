@@ -1,43 +1,356 @@
-use std::sync::mpsc;
+use std::cmp::min;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-use post::Post;
-use shell::{self, Task};
+use time;
+
+use post::{Post, Message_Type};
+use shell::{self, Shell, Task};
+use auth::Authenticator;
+use history::History;
+use commands::{Commands, Visibility};
 use pb;
 
-impl shell::Sender for pb::Sender<ChatUser> {
-    fn send(&self, s: String) {
-        self.send(s).unwrap_or_else(|_| error!("Failed to send command result"));
+// Most posts a single request gets from the history.
+const MAX_REPLAY: usize = 1000;
+
+#[derive(Clone)]
+pub struct CommandResult {
+    request: Post,
+    lines: Vec<String>,
+}
+
+pub struct Reply {
+    channel: pb::Sender<ChatUser>,
+    request: Post,
+}
+
+impl shell::Sender for Reply {
+    fn send(&self, lines: Vec<String>) {
+        let result = CommandResult { request: self.request.clone(), lines: lines };
+        self.channel.send(result).unwrap_or_else(|_| error!("Failed to send command result"));
     }
 }
 
 #[derive(Clone)]
 pub struct ChatUser {
-    pub shell: mpsc::Sender<Task<pb::Sender<ChatUser>>>,
+    pub shell: Shell<Reply>,
+    pub auth: Arc<Authenticator>,
+    pub history: Arc<Mutex<History>>,
+    pub commands: Arc<Commands>,
+    pub topics: Arc<Mutex<HashMap<String, String>>>,
+    pub started: f64,
 }
 
 type User = pb::User<ChatUser>;
 
+impl ChatUser {
+    fn post(&mut self, user: &mut User, mut post: Post) {
+        let login = user.login().unwrap_or("").to_string();
+        // The author may be left empty, the server knows who is talking.
+        if !post.get_author().is_empty() && post.get_author() != login {
+            return user.echo(Post::from_result(
+                format!("You are logged in as {}, not {}", login, post.get_author())));
+        }
+        post.set_author(login.clone());
+        post.set_timestamp(now_ms());
+
+        if post.get_field_type() == Message_Type::COMMAND && post.command().is_none() {
+            return user.echo(Post::from_result("Bad command".to_string()));
+        }
+        if let Some(cmd) = post.command().cloned() {
+            let mut words = cmd.trim().splitn(2, char::is_whitespace);
+            let name = words.next().unwrap_or("").to_string();
+            let args = words.next().unwrap_or("").trim().to_string();
+            match &name[..] {
+                "help" => return self.help(user),
+                "who" => return user.who(),
+                "nick" => return self.nick(user, args),
+                "join" => return self.join(user, args),
+                "topic" => return self.topic(user, args),
+                "uptime" => return self.uptime(user),
+                "me" => {
+                    // Delivered like an ordinary message, to the same room or recipient.
+                    post.set_field_type(Message_Type::MESSAGE);
+                    post.clear_text();
+                    post.mut_text().push(format!("* {} {}", login, args));
+                }
+                _ => {
+                    self.show_command(user, post.clone());
+                    match self.commands.run(&name, &login, &args) {
+                        Some(lines) => self.command_result(user, &post, lines),
+                        None => self.shell.submit(Task {
+                            cmd: cmd.clone(),
+                            user: login.clone(),
+                            reply_to: Reply { channel: user.channel(), request: post },
+                        }),
+                    }
+                    return;
+                }
+            }
+        }
+
+        self.relay(user, post);
+    }
+
+    // Sends the post to the recipient, the room or everyone.
+    fn relay(&mut self, user: &mut User, mut post: Post) {
+        if post.has_recipient() {
+            self.stamp(&mut post);
+            return self.direct(user, post);
+        }
+
+        match post.room().map(|room| room.to_string()) {
+            Some(room) => {
+                if user.is_member(&room) {
+                    self.stamp(&mut post);
+                    user.multicast(room, post)
+                } else {
+                    user.echo(Post::from_result(format!("You are not in {}", room)))
+                }
+            }
+            None => {
+                // Stamp under the lock, so that ids in the history are increasing.
+                let mut history = self.history.lock().unwrap();
+                post.set_id(history.next_id());
+                if post.get_field_type() == Message_Type::MESSAGE {
+                    history.append(&post)
+                        .unwrap_or_else(|e| error!("Failed to save post to history, {}", e));
+                }
+                user.broadcast(post)
+            }
+        }
+    }
+
+    fn show_command(&mut self, user: &mut User, mut post: Post) {
+        match self.commands.visibility() {
+            Visibility::Private => {
+                self.stamp(&mut post);
+                user.echo(post)
+            }
+            Visibility::Public => self.relay(user, post),
+        }
+    }
+
+    fn command_result(&mut self, user: &mut User, request: &Post, lines: Vec<String>) {
+        let mut post = Post::from_text(request.get_author().to_string(), lines);
+        if request.has_room() {
+            post.set_room(request.get_room().to_string());
+        }
+        if request.has_recipient() {
+            post.set_recipient(request.get_recipient().to_string());
+        }
+        post.set_timestamp(now_ms());
+        self.show_command(user, post);
+    }
+
+    fn stamp(&self, post: &mut Post) {
+        post.set_id(self.history.lock().unwrap().next_id());
+    }
+
+    // Server announcements relayed to other users get an id and a time, like posts.
+    fn notice(&self, text: String) -> Post {
+        let mut post = Post::from_result(text);
+        self.stamp(&mut post);
+        post.set_timestamp(now_ms());
+        post
+    }
+
+    fn replay(&mut self, user: &mut User, request: &Post) {
+        let since = if request.has_since() { Some(request.get_since()) } else { None };
+        let limit = if request.has_limit() { request.get_limit() as usize } else { MAX_REPLAY };
+        // Reading may take a while, every broadcast would wait for the lock.
+        let range = self.history.lock().unwrap().find(since, Some(min(limit, MAX_REPLAY)));
+        match range.read() {
+            Ok(posts) => for post in posts {
+                user.echo(post)
+            },
+            Err(e) => {
+                error!("Failed to read history, {}", e);
+                user.echo(Post::from_result("History is not available".to_string()))
+            }
+        }
+    }
+
+    fn help(&mut self, user: &mut User) {
+        let mut lines = vec![
+            "/help -- this list".to_string(),
+            "/who -- who is online".to_string(),
+            "/nick <login> -- change login".to_string(),
+            "/me <action> -- tell what you are doing".to_string(),
+            "/join <room> -- join a room".to_string(),
+            "/topic <room> [topic] -- show or set the topic of a room".to_string(),
+            "/uptime -- how long the server is running".to_string(),
+        ];
+        lines.extend(self.commands.help());
+        lines.push("Other commands are run by the server shell, if allowed".to_string());
+        user.echo(Post::from_text("".to_string(), lines));
+    }
+
+    fn nick(&mut self, user: &mut User, nick: String) {
+        if nick.is_empty() || nick.contains(char::is_whitespace) {
+            return user.echo(Post::from_result("Usage: /nick <login>".to_string()));
+        }
+        // With passwords this keeps users from taking over someone's login.
+        if !self.auth.authenticate(&nick, "") {
+            return user.echo(Post::from_result(format!("{} requires a password", nick)));
+        }
+        let old = user.login().unwrap_or("").to_string();
+        if !user.set_login(nick.clone()) {
+            return user.echo(Post::from_result(format!("{} is already taken", nick)));
+        }
+        let notice = self.notice(format!("{} is now known as {}", old, nick));
+        user.broadcast(notice);
+        user.echo(Post::from_result(format!("You are now known as {}", nick)));
+    }
+
+    fn topic(&mut self, user: &mut User, args: String) {
+        let mut words = args.splitn(2, char::is_whitespace);
+        let room = words.next().unwrap_or("").to_string();
+        let topic = words.next().unwrap_or("").trim().to_string();
+        if room.is_empty() {
+            return user.echo(Post::from_result("Usage: /topic <room> [topic]".to_string()));
+        }
+
+        if topic.is_empty() {
+            let reply = match self.topics.lock().unwrap().get(&room) {
+                Some(topic) => format!("Topic of {}: {}", room, topic),
+                None => format!("{} has no topic", room),
+            };
+            return user.echo(Post::from_result(reply));
+        }
+
+        if !user.is_member(&room) {
+            return user.echo(Post::from_result(format!("You are not in {}", room)));
+        }
+        self.topics.lock().unwrap().insert(room.clone(), topic.clone());
+        let login = user.login().unwrap_or("").to_string();
+        let mut post = self.notice(format!("{} set the topic: {}", login, topic));
+        post.set_room(room.clone());
+        user.multicast(room, post);
+    }
+
+    fn uptime(&mut self, user: &mut User) {
+        let secs = (time::precise_time_s() - self.started) as u64;
+        user.echo(Post::from_result(format!("Up for {}h {}m {}s",
+                                            secs / 3600, secs / 60 % 60, secs % 60)));
+    }
+
+    fn direct(&mut self, user: &mut User, post: Post) {
+        let recipient = post.get_recipient().to_string();
+        if !user.is_online(&recipient) {
+            return user.echo(Post::from_result(format!("{} is not online", recipient)));
+        }
+        if user.login() != Some(&recipient[..]) {
+            user.echo(post.clone());
+        }
+        user.send_to(recipient, post);
+    }
+
+    fn join(&mut self, user: &mut User, room: String) {
+        if room.is_empty() {
+            return user.echo(Post::from_result("Room name is required".to_string()));
+        }
+        user.join(room.clone());
+        user.echo(Post::from_result(format!("Joined {}", room)));
+    }
+
+    fn leave(&mut self, user: &mut User, room: String) {
+        let reply = if user.leave(&room) {
+            format!("Left {}", room)
+        } else {
+            format!("You are not in {}", room)
+        };
+        user.echo(Post::from_result(reply));
+    }
+
+    fn list_rooms(&mut self, user: &mut User) {
+        let rooms = user.rooms().into_iter()
+            .map(|(room, n_members)| format!("{} ({})", room, n_members))
+            .collect::<Vec<_>>();
+        if rooms.is_empty() {
+            return user.echo(Post::from_result("No rooms".to_string()));
+        }
+        user.echo(Post::from_text("".to_string(), rooms));
+    }
+}
+
 impl pb::ProtoHandler for ChatUser {
     type Proto = Post;
-    type Message = String;
+    type Message = CommandResult;
+
+    fn handshake(&mut self, user: &mut User, post: Post) {
+        if post.get_field_type() != Message_Type::LOGIN {
+            return user.echo(Post::from_result("Login required".to_string()));
+        }
+        let login = post.get_author().to_string();
+        if login.is_empty() {
+            return user.echo(Post::from_result("Login must not be empty".to_string()));
+        }
+        if !self.auth.authenticate(&login, post.get_password()) {
+            info!("Failed login attempt for {}", login);
+            return user.echo(Post::from_result("Invalid login or password".to_string()));
+        }
+        // Refused rather than failed, the client may retry once the login is
+        // free, say when its own previous connection times out.
+        if !user.set_login(login.clone()) {
+            return user.echo(Post::rejected(format!("{} is already taken", login)));
+        }
+        let notice = self.notice(format!("{} joined", login));
+        user.broadcast(notice);
+        user.echo(Post::logged_in(login));
+        if post.has_since() || post.has_limit() {
+            self.replay(user, &post);
+        }
+    }
 
     fn recv(&mut self, user: &mut User, post: Post) {
-        if let Some(cmd) =  post.command() {
-            let task = Task {
-                cmd: cmd.to_string(),
-                reply_to: user.channel(),
-            };
+        match post.get_field_type() {
+            Message_Type::LOGIN => user.echo(Post::from_result("Already logged in".to_string())),
+            Message_Type::JOIN => self.join(user, post.get_room().to_string()),
+            Message_Type::LEAVE => self.leave(user, post.get_room().to_string()),
+            Message_Type::ROOMS => self.list_rooms(user),
+            Message_Type::WHO => user.who(),
+            Message_Type::HISTORY => self.replay(user, &post),
+            Message_Type::PING => user.echo(Post::pong()),
+            // Any message counts as a sign of life, nothing else to do.
+            Message_Type::PONG => {}
+            Message_Type::REJECTED => user.echo(Post::from_result("Unexpected message".to_string())),
+            Message_Type::COMMAND | Message_Type::MESSAGE => self.post(user, post),
+        }
+    }
 
-            self.shell.send(task)
-                .unwrap_or_else(|e| error!("Failed to send command, {}", e));
+    fn notify(&mut self, user: &mut User, result: CommandResult) {
+        self.command_result(user, &result.request, result.lines);
+    }
+
+    fn disconnected(&mut self, user: &mut User) {
+        if let Some(login) = user.login().map(|login| login.to_string()) {
+            let notice = self.notice(format!("{} left", login));
+            user.broadcast(notice);
         }
-        user.broadcast(post)
     }
 
-    fn notify(&mut self, user: &mut User, result: String) {
-        let post = Post::from_result(result);
-        user.echo(post);
+    fn protocol_error(&mut self, user: &mut User, reason: String) {
+        user.echo(Post::from_result(format!("Disconnected: {}", reason)));
+    }
+
+    fn ping(&self) -> Post {
+        Post::ping()
     }
-}
 
+    fn rejected(&self, reason: String) -> Post {
+        Post::rejected(reason)
+    }
 
+    fn users_online(&mut self, user: &mut User, mut logins: Vec<String>) {
+        logins.sort();
+        logins.dedup();
+        user.echo(Post::from_text("".to_string(), logins));
+    }
+}
+
+fn now_ms() -> i64 {
+    let now = time::get_time();
+    now.sec * 1000 + (now.nsec / 1000000) as i64
+}
@@ -0,0 +1,256 @@
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::io::{self, Read};
+
+use protobuf;
+
+// Lengths are varint encoded u32, which takes at most five bytes.
+const MAX_VARINT_LEN: usize = 5;
+
+// Memory held by the buffer once a message this large has been consumed is
+// given back.
+const SHRINK_ABOVE: usize = 1024 * 1024;
+
+// `read_from` asks for at least this many bytes, more if the buffer already
+// has room for them, but never more than `MAX_READ`: the room is zeroed
+// before every read, and a huge message must not be zeroed over and over.
+const MIN_READ: usize = 4 * 1024;
+const MAX_READ: usize = 64 * 1024;
+
+// Framing of length delimited protobuf messages, without any I/O: bytes go
+// in through `feed`, complete messages come out of `next_frame`.
+pub struct Codec<M: protobuf::MessageStatic> {
+    buffer: Vec<u8>,
+    // Start of the first message not yet returned by `next_frame`.
+    pos: usize,
+    max_size: usize,
+    m: PhantomData<M>,
+}
+
+// The error inside `io::Error` of kind `InvalidData` when a peer announces
+// a message larger than the codec accepts.
+#[derive(Debug)]
+pub struct FrameTooLarge {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "message of {} bytes is larger than {} bytes", self.size, self.max_size)
+    }
+}
+
+impl Error for FrameTooLarge {
+    fn description(&self) -> &str {
+        "message is too large"
+    }
+}
+
+pub fn is_frame_too_large(e: &io::Error) -> bool {
+    e.get_ref().map_or(false, |e| e.downcast_ref::<FrameTooLarge>().is_some())
+}
+
+pub fn encode<M: protobuf::Message>(msg: &M) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(msg.compute_size() as usize + MAX_VARINT_LEN);
+    msg.write_length_delimited_to_vec(&mut buf).unwrap();
+    buf
+}
+
+impl<M: protobuf::MessageStatic> Codec<M> {
+    pub fn new() -> Codec<M> {
+        Codec::with_max_size(::std::u32::MAX as usize)
+    }
+
+    pub fn with_max_size(max_size: usize) -> Codec<M> {
+        Codec {
+            buffer: Vec::new(),
+            pos: 0,
+            max_size: max_size,
+            m: PhantomData,
+        }
+    }
+
+    pub fn feed(&mut self, data: &[u8]) {
+        self.compact();
+        self.buffer.extend_from_slice(data);
+    }
+
+    // Makes a single read from the source straight into the buffer, returns
+    // the number of bytes read.
+    pub fn read_from<R: Read>(&mut self, source: &mut R) -> io::Result<usize> {
+        self.compact();
+        let len = self.buffer.len();
+        let room = ::std::cmp::max(self.buffer.capacity() - len, MIN_READ);
+        let room = ::std::cmp::min(room, MAX_READ);
+        self.buffer.resize(len + room, 0);
+        let result = source.read(&mut self.buffer[len..]);
+        self.buffer.truncate(len + *result.as_ref().unwrap_or(&0));
+        result
+    }
+
+    fn compact(&mut self) {
+        if self.pos == self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+            if self.buffer.capacity() > SHRINK_ABOVE {
+                self.buffer.shrink_to_fit();
+            }
+        } else if self.pos > 0 && self.pos >= self.buffer.len() - self.pos {
+            // Move the unfinished message to the front, it is shorter than
+            // what we've already consumed.
+            self.buffer.drain(..self.pos);
+            self.pos = 0;
+        }
+    }
+
+    // The next complete message, if it is already fed.
+    pub fn next_frame(&mut self) -> io::Result<Option<M>> {
+        let (header, len) = match try!(self.frame_len()) {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        if self.buffered() < header + len {
+            return Ok(None);
+        }
+
+        let body = &self.buffer[self.pos + header..self.pos + header + len];
+        self.pos += header + len;
+        match protobuf::parse_from_bytes::<M>(body) {
+            Ok(m) => Ok(Some(m)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+    }
+
+    pub fn encode_into(&self, msg: &M, out: &mut Vec<u8>) {
+        msg.write_length_delimited_to_vec(out).unwrap();
+    }
+
+    // Bytes fed, but not returned as messages yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.pos
+    }
+
+    // Call when the input is over: fails if it ended in the middle of a message.
+    pub fn eof(&self) -> io::Result<()> {
+        if self.buffered() == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                               "connection closed in the middle of a message"))
+        }
+    }
+
+    // Length of the varint header and of the message body, once the
+    // whole header is fed.
+    fn frame_len(&self) -> io::Result<Option<(usize, usize)>> {
+        let data = &self.buffer[self.pos..];
+        let mut len = 0u64;
+        let mut header = None;
+        for (i, &byte) in data.iter().take(MAX_VARINT_LEN).enumerate() {
+            len |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                header = Some(i + 1);
+                break;
+            }
+        }
+        let header = match header {
+            Some(header) if len <= ::std::u32::MAX as u64 => header,
+            None if data.len() < MAX_VARINT_LEN => return Ok(None),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "malformed message length")),
+        };
+        let len = len as usize;
+        if len > self.max_size {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, FrameTooLarge {
+                size: len,
+                max_size: self.max_size,
+            }));
+        }
+        Ok(Some((header, len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use post::Post;
+    use super::{Codec, encode, is_frame_too_large, MAX_READ};
+
+    #[test]
+    fn feed_byte_by_byte() {
+        let post = Post::from_text("Alice".to_string(), vec!["hello".to_string()]);
+        let data = encode(&post);
+        let mut codec = Codec::new();
+        for (i, byte) in data.iter().enumerate() {
+            assert!(codec.next_frame().unwrap().is_none());
+            assert_eq!(codec.buffered(), i);
+            codec.feed(&[*byte]);
+        }
+        assert_eq!(codec.next_frame().unwrap(), Some(post));
+        assert_eq!(codec.buffered(), 0);
+        assert!(codec.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn eof() {
+        let data = encode(&Post::who());
+        let mut codec = Codec::<Post>::new();
+        assert!(codec.eof().is_ok());
+        codec.feed(&data[..1]);
+        assert_eq!(codec.eof().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        codec.feed(&data[1..]);
+        assert!(codec.next_frame().unwrap().is_some());
+        assert!(codec.eof().is_ok());
+    }
+
+    #[test]
+    fn too_large() {
+        let data = encode(&Post::from_result(vec!["x"; 100].concat()));
+        let mut codec = Codec::<Post>::with_max_size(99);
+        // Known from the header alone.
+        codec.feed(&data[..1]);
+        assert!(is_frame_too_large(&codec.next_frame().unwrap_err()));
+    }
+
+    #[test]
+    fn read_from() {
+        let posts = vec![Post::who(), Post::from_result(vec!["x"; 10000].concat()), Post::who()];
+        let data = posts.iter().flat_map(encode).collect::<Vec<_>>();
+        let mut source = &data[..];
+        let mut codec = Codec::<Post>::new();
+        let mut decoded = Vec::new();
+        while codec.read_from(&mut source).unwrap() > 0 {
+            while let Some(post) = codec.next_frame().unwrap() {
+                decoded.push(post);
+            }
+        }
+        assert!(codec.eof().is_ok());
+        assert_eq!(decoded, posts);
+    }
+
+    #[test]
+    fn read_from_in_chunks() {
+        struct Bounded<'a>(&'a [u8]);
+
+        impl<'a> io::Read for Bounded<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                assert!(buf.len() <= MAX_READ);
+                self.0.read(buf)
+            }
+        }
+
+        let post = Post::from_result(vec!["x"; 4 * MAX_READ].concat());
+        let data = encode(&post);
+        let mut source = Bounded(&data);
+        let mut codec = Codec::<Post>::new();
+        let mut decoded = None;
+        while codec.read_from(&mut source).unwrap() > 0 {
+            if let Some(post) = codec.next_frame().unwrap() {
+                decoded = Some(post);
+            }
+        }
+        assert_eq!(decoded, Some(post));
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    // Only the requester sees the command and its result.
+    Private,
+    // Both the command and the result are sent where a message would go.
+    Public,
+}
+
+pub type Handler = Box<Fn(&str, &str) -> Vec<String> + Send + Sync>;
+
+struct Command {
+    help: String,
+    handler: Handler,
+}
+
+// Commands added by the library user. They run inside the server, get the
+// login of the requester and the rest of the command line, and the returned
+// lines are the result. Visibility applies to these and to shell commands.
+pub struct Commands {
+    commands: BTreeMap<String, Command>,
+    visibility: Visibility,
+}
+
+impl Commands {
+    pub fn new() -> Commands {
+        Commands {
+            commands: BTreeMap::new(),
+            visibility: Visibility::Private,
+        }
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = visibility;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn register<F>(&mut self, name: &str, help: &str, handler: F)
+        where F: Fn(&str, &str) -> Vec<String> + Send + Sync + 'static {
+        self.commands.insert(name.to_string(), Command {
+            help: help.to_string(),
+            handler: Box::new(handler),
+        });
+    }
+
+    pub fn run(&self, name: &str, login: &str, args: &str) -> Option<Vec<String>> {
+        self.commands.get(name).map(|command| (command.handler)(login, args))
+    }
+
+    pub fn help(&self) -> Vec<String> {
+        self.commands.iter()
+            .map(|(name, command)| format!("/{} -- {}", name, command.help))
+            .collect()
+    }
+}
@@ -0,0 +1,306 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::cmp;
+
+use post::Post;
+use proto_reader::ProtoReader;
+
+// Ids are reserved on disk in blocks of this size.
+const ID_BLOCK: u64 = 1000;
+
+// Append-only log of length-delimited posts, exactly as they are sent
+// over the wire. An in-memory index maps post ids to file offsets.
+//
+// History also hands out post ids. Not every post is saved, so the
+// highest reserved id is kept in a separate `.ids` file, and ids are
+// never reused after a restart.
+pub struct History {
+    // `None` when nothing is saved, see `disabled`.
+    log: Option<Log>,
+    index: Vec<(u64, u64)>,
+    len: u64,
+    last_id: u64,
+    reserved: u64,
+}
+
+struct Log {
+    path: PathBuf,
+    file: File,
+    ids: File,
+}
+
+impl History {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<History> {
+        let path = path.as_ref();
+        let mut file = try!(OpenOptions::new().read(true).append(true).create(true).open(path));
+
+        // Only the index is kept, posts are read one at a time.
+        let mut index = Vec::new();
+        let mut reader = ProtoReader::<Post>::new();
+        let mut read = 0;
+        let mut offset = 0;
+        loop {
+            while let Some(post) = try!(reader.next()) {
+                index.push((post.get_id(), offset));
+                offset = read - reader.buffered() as u64;
+            }
+            match try!(reader.fill(&mut file)) {
+                0 => break,
+                n => read += n as u64,
+            }
+        }
+
+        if offset < read {
+            warn!("Dropping {} bytes of incomplete post at the end of history", read - offset);
+            try!(file.set_len(offset));
+        }
+
+        let mut ids = try!(OpenOptions::new().read(true).write(true).create(true)
+                                             .open(path.with_extension("ids")));
+        let mut reserved = String::new();
+        try!(ids.read_to_string(&mut reserved));
+        let reserved = reserved.trim().parse().unwrap_or(0);
+        // Ids reserved by the previous run may have been handed out, skip them all.
+        let last_id = cmp::max(reserved, index.last().map_or(0, |&(id, _)| id));
+
+        info!("Loaded {} posts from history", index.len());
+        Ok(History {
+            log: Some(Log { path: path.to_path_buf(), file: file, ids: ids }),
+            index: index,
+            len: offset,
+            last_id: last_id,
+            reserved: last_id,
+        })
+    }
+
+    // Hands out ids, but keeps no posts: queries find nothing, and ids
+    // start over with every run.
+    pub fn disabled() -> History {
+        History { log: None, index: Vec::new(), len: 0, last_id: 0, reserved: 0 }
+    }
+
+    pub fn last_id(&self) -> u64 {
+        self.last_id
+    }
+
+    pub fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        if self.last_id > self.reserved {
+            let reserved = self.last_id + ID_BLOCK;
+            match self.reserve(reserved) {
+                Ok(()) => self.reserved = reserved,
+                Err(e) => error!("Failed to reserve post ids, {}", e),
+            }
+        }
+        self.last_id
+    }
+
+    fn reserve(&mut self, reserved: u64) -> io::Result<()> {
+        let ids = match self.log {
+            Some(ref mut log) => &mut log.ids,
+            None => return Ok(()),
+        };
+        try!(ids.seek(SeekFrom::Start(0)));
+        try!(ids.set_len(0));
+        try!(ids.write_all(format!("{}\n", reserved).as_bytes()));
+        ids.sync_data()
+    }
+
+    // Ids must be increasing, which is up to the caller.
+    pub fn append(&mut self, post: &Post) -> io::Result<()> {
+        let file = match self.log {
+            Some(ref mut log) => &mut log.file,
+            None => return Ok(()),
+        };
+        let id = post.get_id();
+        let bytes = post.to_bytes();
+        if let Err(e) = file.write_all(&bytes) {
+            try!(file.set_len(self.len));
+            return Err(e);
+        }
+        self.index.push((id, self.len));
+        self.len += bytes.len() as u64;
+        Ok(())
+    }
+
+    // Posts with id greater than `since`, but no more than `limit` most recent ones.
+    pub fn query(&self, since: Option<u64>, limit: Option<usize>) -> io::Result<Vec<Post>> {
+        self.find(since, limit).read()
+    }
+
+    // Where the posts of `query` are. They can be read without holding on to
+    // the history, posts appended meanwhile don't get in the way.
+    pub fn find(&self, since: Option<u64>, limit: Option<usize>) -> Range {
+        let mut start = match since {
+            Some(since) => match self.index.binary_search_by(|&(id, _)| id.cmp(&since)) {
+                Ok(i) => i + 1,
+                Err(i) => i,
+            },
+            None => 0,
+        };
+        if let Some(limit) = limit {
+            start = cmp::max(start, self.index.len().saturating_sub(limit));
+        }
+
+        let offset = self.index.get(start).map_or(self.len, |&(_, offset)| offset);
+        Range {
+            path: self.log.as_ref().map(|log| log.path.clone()),
+            offset: offset,
+            len: self.len - offset,
+            count: self.index.len() - start,
+        }
+    }
+}
+
+pub struct Range {
+    path: Option<PathBuf>,
+    offset: u64,
+    len: u64,
+    count: usize,
+}
+
+impl Range {
+    pub fn read(&self) -> io::Result<Vec<Post>> {
+        let path = match self.path {
+            Some(ref path) if self.count > 0 => path,
+            _ => return Ok(Vec::new()),
+        };
+        let mut file = try!(File::open(path));
+        try!(file.seek(SeekFrom::Start(self.offset)));
+        let mut reader = file.take(self.len);
+        let mut proto_reader = ProtoReader::new();
+        (0..self.count).map(|_| proto_reader.read(&mut reader)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use time;
+
+    use post::Post;
+    use super::{History, ID_BLOCK};
+
+    // A fresh log in the temporary directory, removed when dropped.
+    struct TempLog(PathBuf);
+
+    impl TempLog {
+        fn new(name: &str) -> TempLog {
+            TempLog(env::temp_dir().join(format!("auchat-{}-{}.log", name, time::precise_time_ns())))
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            fs::remove_file(&self.0).ok();
+            fs::remove_file(self.0.with_extension("ids")).ok();
+        }
+    }
+
+    fn post(history: &mut History) -> Post {
+        let mut post = Post::from_result("hello".to_string());
+        post.set_id(history.next_id());
+        post
+    }
+
+    fn ids(history: &History, since: Option<u64>, limit: Option<usize>) -> Vec<u64> {
+        history.query(since, limit).unwrap().iter().map(|post| post.get_id()).collect()
+    }
+
+    #[test]
+    fn query() {
+        let log = TempLog::new("query");
+        let mut history = History::open(&log.0).unwrap();
+        assert_eq!(ids(&history, None, None), vec![]);
+        for _ in 0..5 {
+            let post = post(&mut history);
+            history.append(&post).unwrap();
+        }
+
+        assert_eq!(ids(&history, None, None), vec![1, 2, 3, 4, 5]);
+        assert_eq!(ids(&history, Some(0), None), vec![1, 2, 3, 4, 5]);
+        assert_eq!(ids(&history, Some(2), None), vec![3, 4, 5]);
+        assert_eq!(ids(&history, None, Some(2)), vec![4, 5]);
+        assert_eq!(ids(&history, Some(3), Some(10)), vec![4, 5]);
+        assert_eq!(ids(&history, Some(1), Some(1)), vec![5]);
+        assert_eq!(ids(&history, Some(5), None), vec![]);
+        assert_eq!(ids(&history, None, Some(0)), vec![]);
+    }
+
+    #[test]
+    fn query_with_holes() {
+        let log = TempLog::new("holes");
+        let mut history = History::open(&log.0).unwrap();
+        for i in 0..6 {
+            let post = post(&mut history);
+            // Room posts and direct messages take ids, but are not saved.
+            if i % 2 == 0 {
+                history.append(&post).unwrap();
+            }
+        }
+        assert_eq!(ids(&history, None, None), vec![1, 3, 5]);
+        assert_eq!(ids(&history, Some(2), None), vec![3, 5]);
+        assert_eq!(ids(&history, Some(4), None), vec![5]);
+    }
+
+    #[test]
+    fn reopen() {
+        let log = TempLog::new("reopen");
+        {
+            let mut history = History::open(&log.0).unwrap();
+            for _ in 0..3 {
+                let post = post(&mut history);
+                history.append(&post).unwrap();
+            }
+            // Handed out, but never saved.
+            history.next_id();
+        }
+
+        let mut history = History::open(&log.0).unwrap();
+        assert_eq!(ids(&history, None, None), vec![1, 2, 3]);
+        let id = history.next_id();
+        assert!(id > 4, "id {} reused after restart", id);
+        assert!(id <= 4 + ID_BLOCK + 1);
+    }
+
+    #[test]
+    fn truncated_tail() {
+        let log = TempLog::new("tail");
+        let last = {
+            let mut history = History::open(&log.0).unwrap();
+            for _ in 0..2 {
+                let post = post(&mut history);
+                history.append(&post).unwrap();
+            }
+            post(&mut history)
+        };
+        let len = fs::metadata(&log.0).unwrap().len();
+        // A post cut short by a crash.
+        let bytes = last.to_bytes();
+        OpenOptions::new().append(true).open(&log.0).unwrap()
+                          .write_all(&bytes[..bytes.len() - 1]).unwrap();
+
+        let mut history = History::open(&log.0).unwrap();
+        assert_eq!(fs::metadata(&log.0).unwrap().len(), len);
+        assert_eq!(ids(&history, None, None), vec![1, 2]);
+        let post = post(&mut history);
+        history.append(&post).unwrap();
+        assert_eq!(ids(&history, Some(2), None), vec![post.get_id()]);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut history = History::disabled();
+        for _ in 0..3 {
+            let post = post(&mut history);
+            history.append(&post).unwrap();
+        }
+        assert_eq!(history.last_id(), 3);
+        assert_eq!(ids(&history, None, None), vec![]);
+    }
+}
@@ -4,20 +4,195 @@ extern crate mio;
 extern crate protobuf;
 extern crate byteorder;
 extern crate time;
+extern crate crypto;
 
 use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 pub mod post;
+pub mod codec;
 pub mod proto_reader;
+pub mod auth;
+pub mod history;
+pub mod shell;
+pub mod commands;
 mod pb;
 mod chat;
-mod shell;
 
+use auth::{Authenticator, AllowAnyone};
+use post::Post;
+use history::History;
+use commands::Commands;
 
-pub fn start_server(addr: SocketAddr, n_workers: usize) {
-    println!("Starting server at {}", addr);
-    let shell = shell::start();
-    let handler = chat::ChatUser { shell: shell };
+pub use pb::{SendLimit, Overflow, Timeouts, ConnectionLimits, Metrics, Error};
 
-    pb::start_server(addr, handler, n_workers);
+
+// Everything but the address has a default matching the defaults of
+// `bin/server.rs`, except for the history: nothing is saved unless one
+// is given.
+pub struct Builder {
+    addr: SocketAddr,
+    n_workers: usize,
+    auth: Arc<Authenticator>,
+    history: History,
+    shell: shell::Config,
+    commands: Commands,
+    limit: SendLimit,
+    max_frame: usize,
+    timeouts: Timeouts,
+    limits: ConnectionLimits,
+    metrics: Arc<Metrics>,
+}
+
+impl Builder {
+    pub fn new(addr: SocketAddr) -> Builder {
+        Builder {
+            addr: addr,
+            n_workers: 1,
+            auth: Arc::new(AllowAnyone),
+            history: History::disabled(),
+            shell: shell::Config::default(),
+            commands: Commands::new(),
+            limit: SendLimit { max_bytes: 1024 * 1024 * 1024, overflow: Overflow::Pause },
+            max_frame: 1024 * 1024 * 1024,
+            timeouts: Timeouts {
+                idle_ms: 0,
+                frame_ms: 30000,
+                handshake_ms: 10000,
+                ping_ms: 60000,
+                pong_ms: 20000,
+            },
+            limits: ConnectionLimits { max_connections: 100_000, max_per_ip: 0, accept_rate: 0.0 },
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    pub fn workers(mut self, n_workers: usize) -> Builder {
+        self.n_workers = n_workers;
+        self
+    }
+
+    pub fn history(mut self, history: History) -> Builder {
+        self.history = history;
+        self
+    }
+
+    pub fn auth(mut self, auth: Arc<Authenticator>) -> Builder {
+        self.auth = auth;
+        self
+    }
+
+    pub fn shell(mut self, shell: shell::Config) -> Builder {
+        self.shell = shell;
+        self
+    }
+
+    pub fn commands(mut self, commands: Commands) -> Builder {
+        self.commands = commands;
+        self
+    }
+
+    pub fn send_limit(mut self, limit: SendLimit) -> Builder {
+        self.limit = limit;
+        self
+    }
+
+    pub fn max_message(mut self, max_frame: usize) -> Builder {
+        self.max_frame = max_frame;
+        self
+    }
+
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Builder {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn connection_limits(mut self, limits: ConnectionLimits) -> Builder {
+        self.limits = limits;
+        self
+    }
+
+    pub fn metrics(mut self, metrics: Arc<Metrics>) -> Builder {
+        self.metrics = metrics;
+        self
+    }
+
+    // Binds the address and starts the server on background threads.
+    pub fn start(self) -> Result<ServerHandle, Error> {
+        let (shell, pool) = shell::start(self.shell);
+        let handler = chat::ChatUser {
+            shell: shell,
+            auth: self.auth,
+            history: Arc::new(Mutex::new(self.history)),
+            commands: Arc::new(self.commands),
+            topics: Arc::new(Mutex::new(HashMap::new())),
+            started: time::precise_time_s(),
+        };
+
+        match pb::start_server(self.addr, handler, self.n_workers, self.limit, self.max_frame,
+                               self.timeouts, self.limits, self.metrics) {
+            Ok(handle) => Ok(ServerHandle { handle: handle, shell: pool }),
+            Err(e) => {
+                // The handler is gone along with its shell, so the pool stops.
+                pool.join();
+                Err(e)
+            }
+        }
+    }
+}
+
+pub struct ServerHandle {
+    handle: pb::Handle<chat::ChatUser>,
+    shell: shell::Pool,
+}
+
+impl ServerHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.handle.local_addr()
+    }
+
+    // Tells clients the server is going away and gives them up to
+    // `deadline_ms` to receive what they are owed. Does not wait, see `join`.
+    pub fn stop(&self, deadline_ms: u64) {
+        self.handle.stop(Post::from_result("Server is shutting down".to_string()), deadline_ms);
+    }
+
+    // Waits until the server is stopped and running shell commands finish.
+    pub fn join(self) {
+        self.handle.join();
+        self.shell.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    use post::{Post, Message_Type};
+    use proto_reader::ProtoReader;
+    use super::Builder;
+
+    #[test]
+    fn start_stop_join() {
+        let server = Builder::new("127.0.0.1:0".parse().unwrap()).start().unwrap();
+        let addr = server.local_addr();
+        assert!(addr.port() != 0);
+
+        let mut sock = TcpStream::connect(addr).unwrap();
+        let mut reader = ProtoReader::<Post>::new();
+        sock.write_all(&Post::login("alice".to_string(), None).to_bytes()).unwrap();
+        while reader.read(&mut sock).unwrap().get_field_type() != Message_Type::LOGIN {}
+
+        server.stop(1000);
+        server.join();
+        // The goodbye is the last thing the client gets before the server hangs up.
+        let mut last = None;
+        while let Some(post) = reader.read_next(&mut sock).unwrap() {
+            last = Some(post);
+        }
+        let (_, text) = last.unwrap().take();
+        assert_eq!(text, vec!["Server is shutting down".to_string()]);
+    }
 }
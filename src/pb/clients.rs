@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use super::ConnectionLimits;
+
+// Open connections, in total and by the address they come from.
+#[derive(Default)]
+pub struct Clients {
+    total: usize,
+    by_ip: HashMap<IpAddr, usize>,
+}
+
+impl Clients {
+    pub fn new() -> Clients {
+        Clients::default()
+    }
+
+    // Counts the connection in, unless that would break one of the limits.
+    pub fn admit(&mut self, ip: IpAddr, limits: &ConnectionLimits) -> Result<(), String> {
+        if limits.max_connections > 0 && self.total >= limits.max_connections {
+            return Err("too many connections".to_string());
+        }
+        let n = self.by_ip.entry(ip).or_insert(0);
+        if limits.max_per_ip > 0 && *n >= limits.max_per_ip {
+            return Err(format!("too many connections from {}", ip));
+        }
+        *n += 1;
+        self.total += 1;
+        Ok(())
+    }
+
+    pub fn release(&mut self, ip: IpAddr) {
+        let is_empty = match self.by_ip.get_mut(&ip) {
+            Some(n) => {
+                *n -= 1;
+                *n == 0
+            }
+            None => return,
+        };
+        if is_empty {
+            self.by_ip.remove(&ip);
+        }
+        self.total -= 1;
+    }
+}
+
+// A token bucket per address: each one holds up to `rate` connections and
+// refills at `rate` connections per second.
+pub struct Throttle {
+    rate: f64,
+    buckets: HashMap<IpAddr, (f64, u64)>,
+    last_sweep: u64,
+}
+
+impl Throttle {
+    pub fn new(rate: f64) -> Throttle {
+        Throttle {
+            rate: rate,
+            buckets: HashMap::new(),
+            last_sweep: 0,
+        }
+    }
+
+    // `now` is in milliseconds. A rate of 0 lets everyone through.
+    pub fn allow(&mut self, ip: IpAddr, now: u64) -> bool {
+        if self.rate <= 0.0 {
+            return true;
+        }
+        self.sweep(now);
+
+        let rate = self.rate;
+        let capacity = rate.max(1.0);
+        let bucket = self.buckets.entry(ip).or_insert((capacity, now));
+        bucket.0 = (bucket.0 + (now - bucket.1) as f64 * rate / 1000.0).min(capacity);
+        bucket.1 = now;
+        if bucket.0 < 1.0 {
+            return false;
+        }
+        bucket.0 -= 1.0;
+        true
+    }
+
+    // Forgets addresses whose buckets have refilled, they are no different
+    // from addresses never seen.
+    fn sweep(&mut self, now: u64) {
+        if now - self.last_sweep < 1000 {
+            return;
+        }
+        self.last_sweep = now;
+        let rate = self.rate;
+        let capacity = rate.max(1.0);
+        let full = self.buckets.iter()
+            .filter(|&(_, &(tokens, at))| tokens + (now - at) as f64 * rate / 1000.0 >= capacity)
+            .map(|(&ip, _)| ip)
+            .collect::<Vec<_>>();
+        for ip in full {
+            self.buckets.remove(&ip);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::{Clients, Throttle};
+    use super::super::ConnectionLimits;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+    }
+
+    fn limits(max_connections: usize, max_per_ip: usize) -> ConnectionLimits {
+        ConnectionLimits { max_connections: max_connections, max_per_ip: max_per_ip, accept_rate: 0.0 }
+    }
+
+    #[test]
+    fn admit_and_release() {
+        let limits = limits(3, 2);
+        let mut clients = Clients::new();
+        assert!(clients.admit(ip(1), &limits).is_ok());
+        assert!(clients.admit(ip(1), &limits).is_ok());
+        assert!(clients.admit(ip(1), &limits).is_err());
+        assert!(clients.admit(ip(2), &limits).is_ok());
+        // The total is reached, whoever asks.
+        assert!(clients.admit(ip(3), &limits).is_err());
+
+        clients.release(ip(1));
+        assert!(clients.admit(ip(3), &limits).is_ok());
+        clients.release(ip(2));
+        assert!(clients.by_ip.get(&ip(2)).is_none());
+        // Addresses never admitted do not free anything.
+        clients.release(ip(4));
+        assert_eq!(clients.total, 2);
+    }
+
+    #[test]
+    fn no_limits() {
+        let limits = limits(0, 0);
+        let mut clients = Clients::new();
+        for _ in 0..1000 {
+            assert!(clients.admit(ip(1), &limits).is_ok());
+        }
+        assert_eq!(clients.total, 1000);
+    }
+
+    #[test]
+    fn zero_rate() {
+        let mut throttle = Throttle::new(0.0);
+        for _ in 0..1000 {
+            assert!(throttle.allow(ip(1), 0));
+        }
+        assert!(throttle.buckets.is_empty());
+    }
+
+    #[test]
+    fn refill() {
+        let mut throttle = Throttle::new(2.0);
+        // A full bucket to start with.
+        assert!(throttle.allow(ip(1), 10000));
+        assert!(throttle.allow(ip(1), 10000));
+        assert!(!throttle.allow(ip(1), 10000));
+        // Others have buckets of their own.
+        assert!(throttle.allow(ip(2), 10000));
+        // Two per second is one per half a second.
+        assert!(!throttle.allow(ip(1), 10400));
+        assert!(throttle.allow(ip(1), 10500));
+        assert!(!throttle.allow(ip(1), 10500));
+        // A long pause does not fill the bucket past its size.
+        assert!(throttle.allow(ip(1), 60000));
+        assert!(throttle.allow(ip(1), 60000));
+        assert!(!throttle.allow(ip(1), 60000));
+    }
+
+    #[test]
+    fn slow_rate() {
+        let mut throttle = Throttle::new(0.5);
+        assert!(throttle.allow(ip(1), 10000));
+        assert!(!throttle.allow(ip(1), 11000));
+        assert!(throttle.allow(ip(1), 12000));
+    }
+
+    #[test]
+    fn sweep() {
+        let mut throttle = Throttle::new(1.0);
+        assert!(throttle.allow(ip(1), 10000));
+        assert!(throttle.allow(ip(2), 10500));
+        assert_eq!(throttle.buckets.len(), 2);
+        // The first bucket is full again, the second is not yet.
+        assert!(throttle.allow(ip(3), 11200));
+        assert_eq!(throttle.buckets.len(), 2);
+        assert!(!throttle.buckets.contains_key(&ip(1)));
+        // Sweeps happen at most once a second.
+        assert!(throttle.allow(ip(4), 12000));
+        assert_eq!(throttle.buckets.len(), 3);
+    }
+}
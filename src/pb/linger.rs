@@ -0,0 +1,92 @@
+use std::io;
+use std::net::Shutdown;
+
+use mio::{self, Token, EventSet, PollOpt, TryRead, Handler};
+use mio::tcp::TcpStream;
+use mio::util::Slab;
+
+// Sockets lingering at once, the rest are closed right away.
+pub const CAPACITY: usize = 1024;
+
+// How long a refused client has to read the reason and hang up.
+const LINGER_MS: u64 = 5000;
+
+// Closing a socket with unread input resets the connection, and the client
+// may lose the rejection along with it. So refused sockets only shut down
+// their sending side, and what the client still sends is read and thrown
+// away until it hangs up or the time runs out.
+pub struct Lingering {
+    sockets: Slab<(TcpStream, Option<mio::Timeout>)>,
+}
+
+impl Lingering {
+    pub fn new(first: Token) -> Lingering {
+        Lingering { sockets: Slab::new_starting_at(first, CAPACITY) }
+    }
+
+    pub fn contains(&self, token: Token) -> bool {
+        self.sockets.contains(token)
+    }
+
+    // `timeout` is what the event loop gets back when the socket's time is up.
+    pub fn add<H, F>(&mut self, event_loop: &mut mio::EventLoop<H>, sock: TcpStream, timeout: F)
+        where H: Handler, F: FnOnce(Token) -> H::Timeout {
+        if let Err(e) = sock.shutdown(Shutdown::Write) {
+            debug!("Failed to shut down a refused socket, {:?}", e);
+            return;
+        }
+        let token = match self.sockets.insert((sock, None)) {
+            Ok(token) => token,
+            Err(_) => {
+                debug!("Too many refused sockets, closing one right away");
+                return;
+            }
+        };
+        let registered = event_loop.register_opt(&self.sockets[token].0, token, EventSet::readable(),
+                                                 PollOpt::edge() | PollOpt::oneshot());
+        if let Err(e) = registered {
+            debug!("Failed to register a refused socket, {:?}", e);
+            self.sockets.remove(token);
+            return;
+        }
+        match event_loop.timeout_ms(timeout(token), LINGER_MS) {
+            Ok(timer) => self.sockets[token].1 = Some(timer),
+            Err(e) => {
+                error!("Failed to set a timer for a refused socket, {:?}", e);
+                self.sockets.remove(token);
+            }
+        }
+    }
+
+    pub fn ready<H: Handler>(&mut self, event_loop: &mut mio::EventLoop<H>, token: Token) {
+        let done = {
+            let sock = &mut self.sockets[token].0;
+            match drain(sock) {
+                Ok(false) => event_loop.reregister(sock, token, EventSet::readable(),
+                                                   PollOpt::edge() | PollOpt::oneshot()).is_err(),
+                _ => true,
+            }
+        };
+        if done {
+            if let Some((_, Some(timer))) = self.sockets.remove(token) {
+                event_loop.clear_timeout(timer);
+            }
+        }
+    }
+
+    pub fn expire(&mut self, token: Token) {
+        self.sockets.remove(token);
+    }
+}
+
+// Reads everything available, `true` once the client has hung up.
+fn drain(sock: &mut TcpStream) -> io::Result<bool> {
+    let mut buf = [0; 1024];
+    loop {
+        match try!(sock.try_read(&mut buf)) {
+            Some(0) => return Ok(true),
+            Some(_) => {}
+            None => return Ok(false),
+        }
+    }
+}
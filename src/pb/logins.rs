@@ -0,0 +1,51 @@
+use std::collections::{HashMap, HashSet};
+
+use super::rooms::Member;
+
+#[derive(Default)]
+pub struct Logins {
+    connections: HashMap<String, HashSet<Member>>,
+}
+
+impl Logins {
+    pub fn new() -> Logins {
+        Logins::default()
+    }
+
+    pub fn register(&mut self, login: String, member: Member) {
+        self.connections.entry(login).or_insert_with(HashSet::new).insert(member);
+    }
+
+    // Registers the login for the member, unless another member has it.
+    pub fn claim(&mut self, login: &str, member: Member) -> bool {
+        let taken = self.connections.get(login)
+            .map_or(false, |members| members.iter().any(|&other| other != member));
+        if !taken {
+            self.register(login.to_string(), member);
+        }
+        !taken
+    }
+
+    pub fn unregister(&mut self, login: &str, member: Member) {
+        let is_empty = match self.connections.get_mut(login) {
+            Some(members) => {
+                members.remove(&member);
+                members.is_empty()
+            }
+            None => return,
+        };
+        if is_empty {
+            self.connections.remove(login);
+        }
+    }
+
+    pub fn is_online(&self, login: &str) -> bool {
+        self.connections.contains_key(login)
+    }
+
+    pub fn lookup(&self, login: &str) -> Vec<Member> {
+        self.connections.get(login)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_else(Vec::new)
+    }
+}
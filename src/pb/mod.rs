@@ -1,67 +1,357 @@
-use std::net::SocketAddr;
+use std::error;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::net::{SocketAddr, IpAddr};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
 
-use mio::{self, Token};
+use mio::{self, Token, TryWrite};
 use mio::tcp::TcpStream;
 use protobuf;
 
+use codec;
+
 mod server;
 mod worker;
+mod rooms;
+mod logins;
+mod clients;
+mod linger;
+
+use self::rooms::{Rooms, Member};
+use self::logins::Logins;
+
+// Runs the server on background threads, it keeps running until the returned
+// handle is used to stop it.
+pub fn start_server<H: ProtoHandler>(addr: SocketAddr,
+                                     handler: H,
+                                     n_workers: usize,
+                                     limit: SendLimit,
+                                     max_frame: usize,
+                                     timeouts: Timeouts,
+                                     limits: ConnectionLimits,
+                                     metrics: Arc<Metrics>) -> Result<Handle<H>, Error> {
+    server::ProtoServer::start(addr, handler, n_workers, limit, max_frame, timeouts, limits, metrics)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Bind(SocketAddr, io::Error),
+    // Creating or registering with an event loop failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Bind(addr, ref e) => write!(f, "Failed to bind {}: {}", addr, e),
+            Error::Io(ref e) => write!(f, "Failed to start the event loop: {}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Bind(..) => "failed to bind",
+            Error::Io(..) => "failed to start the event loop",
+        }
+    }
+}
+
+pub struct Handle<H: ProtoHandler> {
+    addr: SocketAddr,
+    server: mio::Sender<server::Stop>,
+    threads: Vec<JoinHandle<()>>,
+    marker: PhantomData<H>,
+}
+
+impl<H: ProtoHandler> Handle<H> {
+    // The address the server actually listens on, which differs from the
+    // requested one if it had port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Stops accepting new clients, sends `goodbye` to every connected one and
+    // gives them up to `deadline_ms` to receive what is queued for them.
+    // Returns right away, use `join` to wait for it.
+    pub fn stop(&self, goodbye: H::Proto, deadline_ms: u64) {
+        info!("Shutting down");
+        let goodbye = Arc::new(codec::encode(&goodbye));
+        if let Err(e) = self.server.send(server::Stop(goodbye, deadline_ms)) {
+            warn!("Server is already stopped, {:?}", e);
+        }
+    }
+
+    // Waits until all threads of the server are gone.
+    pub fn join(self) {
+        for t in self.threads {
+            if t.join().is_err() {
+                error!("Server thread panicked");
+            }
+        }
+        info!("Server stopped");
+    }
+}
+
+// What to do when a client does not read fast enough and its send queue
+// holds `SendLimit::max_bytes` or more. A message is always queued when the
+// queue is empty, however large it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    // Drop queued messages, oldest first, to make room for the new one.
+    DropOldest,
+    // Close the connection.
+    Disconnect,
+    // Keep queueing, but stop reading from the client until the queue is half empty.
+    // The connection is closed if the queue still grows to twice the limit.
+    Pause,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SendLimit {
+    pub max_bytes: usize,
+    pub overflow: Overflow,
+}
 
-pub fn start_server<H: ProtoHandler>(addr: SocketAddr, handler: H, n_workers: usize) {
-    server::ProtoServer::start(addr, handler, n_workers);
+// Connections are closed when one of these runs out, 0 disables a timeout.
+#[derive(Clone, Copy, Debug)]
+pub struct Timeouts {
+    // Nothing received from the client.
+    pub idle_ms: u64,
+    // A message started to arrive, but is not complete yet.
+    pub frame_ms: u64,
+    // The client connected, but has not logged in.
+    pub handshake_ms: u64,
+    // A logged in client silent for this long gets a ping from `ProtoHandler::ping`.
+    pub ping_ms: u64,
+    // The pinged client has to send something back within this time.
+    pub pong_ms: u64,
+}
+
+// New clients are refused beyond these, 0 disables a limit.
+#[derive(Clone, Copy, Debug)]
+pub struct ConnectionLimits {
+    pub max_connections: usize,
+    pub max_per_ip: usize,
+    // Connections accepted from a single address per second.
+    pub accept_rate: f64,
+}
+
+#[derive(Default, Debug)]
+pub struct Metrics {
+    dropped_messages: AtomicUsize,
+    dropped_bytes: AtomicUsize,
+    overflow_disconnects: AtomicUsize,
+    pauses: AtomicUsize,
+    rejected_connections: AtomicUsize,
+}
+
+impl Metrics {
+    pub fn new() -> Metrics {
+        Metrics::default()
+    }
+
+    pub fn dropped_messages(&self) -> usize {
+        self.dropped_messages.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped_bytes(&self) -> usize {
+        self.dropped_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn overflow_disconnects(&self) -> usize {
+        self.overflow_disconnects.load(Ordering::Relaxed)
+    }
+
+    pub fn pauses(&self) -> usize {
+        self.pauses.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected_connections(&self) -> usize {
+        self.rejected_connections.load(Ordering::Relaxed)
+    }
+
+    fn dropped(&self, bytes: usize) {
+        self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+        self.dropped_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
 }
 
 pub trait ProtoHandler: Sized + Clone + Send + 'static {
     type Proto: protobuf::Message + protobuf::MessageStatic + Send;
     type Message: Send + Clone;
 
+    fn handshake(&mut self, user: &mut User<Self>, message: Self::Proto);
     fn recv(&mut self, user: &mut User<Self>, message: Self::Proto);
     fn notify(&mut self, user: &mut User<Self>, message: Self::Message);
+    fn disconnected(&mut self, user: &mut User<Self>);
+    fn users_online(&mut self, user: &mut User<Self>, logins: Vec<String>);
+    // The client broke the protocol and is about to be disconnected.
+    fn protocol_error(&mut self, user: &mut User<Self>, reason: String);
+    // A message asking the client to prove it is alive by sending anything.
+    fn ping(&self) -> Self::Proto;
+    // The last message a client refused by `ConnectionLimits` gets.
+    fn rejected(&self, reason: String) -> Self::Proto;
 }
 
 pub struct User<H: ProtoHandler> {
     sender: Sender<H>,
-    broadcast: Option<H::Proto>,
-    echo: Option<H::Proto>,
+    member: Member,
+    login: Option<String>,
+    rooms: Arc<Mutex<Rooms>>,
+    logins: Arc<Mutex<Logins>>,
+    new_login: Option<String>,
+    broadcast: Vec<H::Proto>,
+    multicast: Vec<(String, H::Proto)>,
+    direct: Vec<(String, H::Proto)>,
+    echo: Vec<H::Proto>,
+    who: bool,
+    disconnect: bool,
 }
 
 impl<H: ProtoHandler> User<H> {
-    fn new(token: Token, sender: mio::Sender<WorkerMessage<H>>) -> Self {
+    fn new(worker: usize,
+           token: Token,
+           login: Option<String>,
+           sender: mio::Sender<WorkerMessage<H>>,
+           rooms: Arc<Mutex<Rooms>>,
+           logins: Arc<Mutex<Logins>>) -> Self {
         User {
-            sender: Sender { token: token, sender: sender},
-            broadcast: None,
-            echo: None,
+            sender: Sender { token: token, login: None, sender: sender },
+            member: (worker, token),
+            login: login,
+            rooms: rooms,
+            logins: logins,
+            new_login: None,
+            broadcast: Vec::new(),
+            multicast: Vec::new(),
+            direct: Vec::new(),
+            echo: Vec::new(),
+            who: false,
+            disconnect: false,
         }
     }
 
+    pub fn login(&self) -> Option<&str> {
+        self.login.as_ref().map(|login| &login[..])
+    }
+
+    // Takes the login, unless someone else has it. The check and the
+    // registration happen at once, so two users can't both take it.
+    pub fn set_login(&mut self, login: String) -> bool {
+        {
+            let mut logins = self.logins.lock().unwrap();
+            if !logins.claim(&login, self.member) {
+                return false;
+            }
+            if let Some(ref old) = self.login {
+                if *old != login {
+                    logins.unregister(old, self.member);
+                }
+            }
+        }
+        self.login = Some(login.clone());
+        self.new_login = Some(login);
+        true
+    }
+
+    pub fn is_online(&self, login: &str) -> bool {
+        self.logins.lock().unwrap().is_online(login)
+    }
+
     pub fn channel(&self) -> Sender<H> {
-        self.sender.clone()
+        Sender { login: self.login.clone(), ..self.sender.clone() }
     }
 
     pub fn broadcast(&mut self, message: H::Proto) {
-        self.broadcast = Some(message);
+        self.broadcast.push(message);
     }
 
     pub fn echo(&mut self, message: H::Proto) {
-        self.echo = Some(message);
+        self.echo.push(message);
+    }
+
+    pub fn who(&mut self) {
+        self.who = true;
+    }
+
+    pub fn disconnect(&mut self) {
+        self.disconnect = true;
+    }
+
+    pub fn join(&mut self, room: String) -> bool {
+        self.rooms.lock().unwrap().join(room, self.member)
+    }
+
+    pub fn leave(&mut self, room: &str) -> bool {
+        self.rooms.lock().unwrap().leave(room, self.member)
+    }
+
+    pub fn is_member(&self, room: &str) -> bool {
+        self.rooms.lock().unwrap().is_member(room, self.member)
+    }
+
+    pub fn rooms(&self) -> Vec<(String, usize)> {
+        self.rooms.lock().unwrap().list()
+    }
+
+    pub fn multicast(&mut self, room: String, message: H::Proto) {
+        self.multicast.push((room, message));
+    }
+
+    pub fn send_to(&mut self, login: String, message: H::Proto) {
+        self.direct.push((login, message));
     }
 }
 
 #[derive(Clone)]
 pub struct Sender<H: ProtoHandler> {
     token: Token,
+    // Who the token belonged to when the sender was made.
+    login: Option<String>,
     sender: mio::Sender<WorkerMessage<H>>,
 }
 
 impl<H: ProtoHandler> Sender<H> {
     pub fn send(&self, message: H::Message)
                 -> Result<(), mio::NotifyError<WorkerMessage<H>>> {
-        self.sender.send(WorkerMessage::HandlerMessage(self.token, message))
+        self.sender.send(WorkerMessage::HandlerMessage(self.login.clone(), self.token, message))
     }
 }
 
+// A serialized, length delimited message. It is encoded once and then
+// shared by every connection and worker it is sent to.
+pub type Frame = Arc<Vec<u8>>;
+
 pub enum WorkerMessage<H: ProtoHandler> {
-    NewConnection(TcpStream),
-    HandlerMessage(Token, H::Message),
-    Broadcast(H::Proto),
+    NewConnection(TcpStream, IpAddr),
+    // Like `Direct`, dropped if the token belongs to another login by now.
+    HandlerMessage(Option<String>, Token, H::Message),
+    Broadcast(Frame),
+    // Members of the room when the post was sent, they may have left since.
+    Multicast(String, Vec<Token>, Frame),
+    // The login the token belonged to when the post was sent.
+    Direct(String, Token, Frame),
+    WhoRequest(usize, u64),
+    WhoResponse(u64, Vec<String>),
+    // Say goodbye to everyone and stop once the send queues are flushed or
+    // the deadline in milliseconds passes, whichever comes first.
+    Shutdown(Option<Frame>, u64),
+}
+
+// Tells a refused client why, without waiting for it: the frame is small and
+// goes into the empty socket buffer, if it does not the client learns nothing.
+// The socket is returned to linger, see `linger::Lingering`.
+fn reject<H: ProtoHandler>(handler: &H, mut sock: TcpStream, reason: String, metrics: &Metrics)
+                           -> TcpStream {
+    metrics.rejected_connections.fetch_add(1, Ordering::Relaxed);
+    let frame = codec::encode(&handler.rejected(reason));
+    if let Err(e) = sock.try_write(&frame) {
+        debug!("Failed to send rejection, {:?}", e);
+    }
+    sock
 }
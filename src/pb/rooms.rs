@@ -0,0 +1,61 @@
+use std::collections::{HashMap, HashSet};
+
+use mio::Token;
+
+pub type Member = (usize, Token);
+
+#[derive(Default)]
+pub struct Rooms {
+    members: HashMap<String, HashSet<Member>>,
+}
+
+impl Rooms {
+    pub fn new() -> Rooms {
+        Rooms::default()
+    }
+
+    pub fn join(&mut self, room: String, member: Member) -> bool {
+        self.members.entry(room).or_insert_with(HashSet::new).insert(member)
+    }
+
+    pub fn leave(&mut self, room: &str, member: Member) -> bool {
+        let (removed, is_empty) = match self.members.get_mut(room) {
+            Some(members) => (members.remove(&member), members.is_empty()),
+            None => return false,
+        };
+        if is_empty {
+            self.members.remove(room);
+        }
+        removed
+    }
+
+    pub fn leave_all(&mut self, member: Member) {
+        let mut empty = Vec::new();
+        for (room, members) in self.members.iter_mut() {
+            if members.remove(&member) && members.is_empty() {
+                empty.push(room.clone());
+            }
+        }
+        for room in empty {
+            self.members.remove(&room);
+        }
+    }
+
+    pub fn is_member(&self, room: &str, member: Member) -> bool {
+        self.members.get(room).map_or(false, |members| members.contains(&member))
+    }
+
+    pub fn members(&self, room: &str) -> Vec<Member> {
+        self.members.get(room)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_else(Vec::new)
+    }
+
+    pub fn list(&self) -> Vec<(String, usize)> {
+        let mut rooms = self.members.iter()
+            .map(|(room, members)| (room.clone(), members.len()))
+            .collect::<Vec<_>>();
+        rooms.sort();
+        rooms
+    }
+}
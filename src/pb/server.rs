@@ -1,41 +1,111 @@
 use std::io;
-use std::net::SocketAddr;
+use std::net::{SocketAddr, IpAddr};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use mio::{self, Token, EventSet, PollOpt};
 use mio::tcp::{TcpStream, TcpListener};
+use time;
 
-use super::{ProtoHandler, WorkerMessage};
+use super::{ProtoHandler, WorkerMessage, SendLimit, Timeouts, ConnectionLimits, Metrics};
+use super::{Frame, Handle, Error, reject};
+use super::clients::{Clients, Throttle};
+use super::linger::{self, Lingering};
 use super::worker::{Workers, Worker};
 
 
+// Stop accepting, then tell workers to say goodbye and flush within the deadline.
+pub struct Stop(pub Frame, pub u64);
+
 pub struct ProtoServer<H: ProtoHandler> {
     socket: TcpListener,
     token: Token,
     workers: Workers<H>,
     worker_ptr: usize,
+    stop: Option<Stop>,
+    handler: H,
+    clients: Arc<Mutex<Clients>>,
+    limits: ConnectionLimits,
+    throttle: Throttle,
+    lingering: Lingering,
+    metrics: Arc<Metrics>,
 }
 
 impl<H: ProtoHandler> ProtoServer<H> {
-    pub fn start(addr: SocketAddr, handler: H, n_workers: usize) {
-        let socket = TcpListener::bind(&addr)
-        .ok().expect("Failed to bind address");
-
-        let mut event_loop = mio::EventLoop::<Self>::new()
-        .ok().expect("Failed to create event loop");
+    pub fn start(addr: SocketAddr,
+                 handler: H,
+                 n_workers: usize,
+                 limit: SendLimit,
+                 max_frame: usize,
+                 timeouts: Timeouts,
+                 limits: ConnectionLimits,
+                 metrics: Arc<Metrics>) -> Result<Handle<H>, Error> {
+        let socket = try!(TcpListener::bind(&addr).map_err(|e| Error::Bind(addr, e)));
+        let local_addr = try!(socket.local_addr().map_err(Error::Io));
+
+        let config = mio::EventLoopConfig {
+            timer_capacity: linger::CAPACITY,
+            ..mio::EventLoopConfig::default()
+        };
+        let mut event_loop = try!(mio::EventLoop::<Self>::configured(config).map_err(Error::Io));
 
-        let workers = Worker::start(handler, n_workers);
+        let clients = Arc::new(Mutex::new(Clients::new()));
+        let (workers, mut threads) = try!(Worker::start(handler.clone(), n_workers, limit, max_frame,
+                                                        timeouts, limits.max_connections,
+                                                        clients.clone(), metrics.clone())
+                                          .map_err(Error::Io));
         let mut server = ProtoServer {
             socket: socket,
             token: mio::Token(1),
             workers: workers,
             worker_ptr: 0,
+            stop: None,
+            handler: handler,
+            clients: clients,
+            limits: limits,
+            throttle: Throttle::new(limits.accept_rate),
+            lingering: Lingering::new(mio::Token(2)),
+            metrics: metrics,
         };
-        server.register(&mut event_loop)
-              .ok().expect("Failed to register server with event loop");
+        if let Err(e) = server.register(&mut event_loop) {
+            server.stop_workers();
+            for t in threads {
+                t.join().ok();
+            }
+            return Err(Error::Io(e));
+        }
+
+        let channel = event_loop.channel();
+        threads.push(thread::spawn(move || {
+            if let Err(e) = event_loop.run(&mut server) {
+                error!("Server event loop failed, {:?}", e);
+            }
+            // Only this thread hands connections to workers, so none can
+            // arrive after the workers are told to stop.
+            server.stop_workers();
+        }));
+
+        Ok(Handle {
+            addr: local_addr,
+            server: channel,
+            threads: threads,
+            marker: PhantomData,
+        })
+    }
 
-        event_loop.run(&mut server)
-                  .ok().expect("Failed to start event loop");
+    fn stop_workers(&mut self) {
+        let (goodbye, deadline_ms) = match self.stop.take() {
+            Some(Stop(goodbye, deadline_ms)) => (Some(goodbye), deadline_ms),
+            None => (None, 0),
+        };
+        for w in self.workers.iter() {
+            if let Err(e) = w.send(WorkerMessage::Shutdown(goodbye.clone(), deadline_ms)) {
+                error!("cannot stop a worker, {:?}", e);
+            }
+        }
     }
+
     fn accept(&mut self, event_loop: &mut mio::EventLoop<Self>) {
         info!("accepted a new client socket");
         let socket = match self.socket.accept() {
@@ -52,19 +122,40 @@ impl<H: ProtoHandler> ProtoServer<H> {
             }
         };
 
-        if let Err(e) = self.round_robin(socket) {
+        let ip = match socket.peer_addr() {
+            Ok(addr) => addr.ip(),
+            Err(e) => {
+                error!("Failed to get address of a new client, {:?}", e);
+                self.reregister(event_loop);
+                return;
+            }
+        };
+
+        if let Err(reason) = self.admit(ip) {
+            warn!("Refusing connection from {}: {}", ip, reason);
+            let socket = reject(&self.handler, socket, reason, &self.metrics);
+            self.lingering.add(event_loop, socket, |token| token);
+        } else if let Err(e) = self.round_robin(socket, ip) {
             error!("Failed to deliver connection to client, {:?}", e);
+            self.clients.lock().unwrap().release(ip);
         }
 
         self.reregister(event_loop);
     }
 
-    fn round_robin(&mut self, sock: TcpStream)
+    fn admit(&mut self, ip: IpAddr) -> Result<(), String> {
+        if !self.throttle.allow(ip, time::precise_time_ns() / 1_000_000) {
+            return Err("connecting too often".to_string());
+        }
+        self.clients.lock().unwrap().admit(ip, &self.limits)
+    }
+
+    fn round_robin(&mut self, sock: TcpStream, ip: IpAddr)
     -> Result<(), mio::NotifyError<WorkerMessage<H>>> {
         self.worker_ptr += 1;
         self.worker_ptr %= self.workers.len();
 
-        let msg = WorkerMessage::NewConnection(sock);
+        let msg = WorkerMessage::NewConnection(sock, ip);
         self.workers[self.worker_ptr].send(msg)
     }
 
@@ -96,8 +187,9 @@ impl<H: ProtoHandler> ProtoServer<H> {
 }
 
 impl<H: ProtoHandler> mio::Handler for ProtoServer<H> {
-    type Timeout = ();
-    type Message = ();
+    // Lingering sockets, the only thing the server sets timers for.
+    type Timeout = Token;
+    type Message = Stop;
 
     fn ready(&mut self,
     event_loop: &mut mio::EventLoop<Self>,
@@ -105,6 +197,10 @@ impl<H: ProtoHandler> mio::Handler for ProtoServer<H> {
     events: mio::EventSet) {
         debug!("events = {:?}", events);
         assert!(token != mio::Token(0), "[BUG]: Received event for Token(0)");
+        if self.lingering.contains(token) {
+            self.lingering.ready(event_loop, token);
+            return;
+        }
         assert!(self.token == token, "Received writable event for server");
 
         if events.is_error() {
@@ -119,5 +215,16 @@ impl<H: ProtoHandler> mio::Handler for ProtoServer<H> {
             self.accept(event_loop);
         }
     }
+
+    // The listening socket is closed when the server thread is done.
+    fn notify(&mut self, event_loop: &mut mio::EventLoop<Self>, stop: Stop) {
+        info!("No longer accepting connections");
+        self.stop = Some(stop);
+        event_loop.shutdown();
+    }
+
+    fn timeout(&mut self, _event_loop: &mut mio::EventLoop<Self>, token: Token) {
+        self.lingering.expire(token);
+    }
 }
 
@@ -1,32 +1,108 @@
 use std::io;
+use std::net::IpAddr;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 use protobuf;
 
 use mio::{self, Token, EventSet, PollOpt, TryWrite, Handler};
-use mio::buf::{Buf, ByteBuf};
 use mio::tcp::TcpStream;
 
 use proto_reader::ProtoReader;
+use pb::{SendLimit, Overflow, Timeouts, Metrics, Frame};
+use super::utils::now_ms;
+
+pub enum Deadline {
+    Close(String),
+    Ping,
+}
 
 pub struct Connection<M: protobuf::MessageStatic> {
     pub token: mio::Token,
+    pub login: Option<String>,
+    pub peer: IpAddr,
     socket: TcpStream,
     interest: EventSet,
-    send_queue: Vec<ByteBuf>,
+    closing: bool,
+    paused: bool,
+    // Frames are shared with other connections, so each connection only
+    // remembers how much of the front frame it has already written.
+    send_queue: VecDeque<Frame>,
+    offset: usize,
+    // Bytes not yet written to the socket.
+    queued: usize,
+    limit: SendLimit,
+    metrics: Arc<Metrics>,
     proto_reader: ProtoReader<M>,
+    // Milliseconds, as returned by `now_ms`.
+    connected_at: u64,
+    last_read: u64,
+    partial_since: Option<u64>,
+    // Reset when anything arrives from the client.
+    pub pinged_at: Option<u64>,
+    // When the pending timer of the connection fires, and its handle.
+    pub timer: Option<(u64, mio::Timeout)>,
 }
 
 impl<M: protobuf::MessageStatic> Connection<M> {
-    pub fn new(socket: TcpStream, token: Token) -> Self {
+    pub fn new(socket: TcpStream,
+               token: Token,
+               peer: IpAddr,
+               limit: SendLimit,
+               max_frame: usize,
+               metrics: Arc<Metrics>) -> Self {
         Connection {
             token: token,
+            login: None,
+            peer: peer,
             socket: socket,
             interest: EventSet::hup(),
-            send_queue: Vec::new(),
-            proto_reader: ProtoReader::new(),
+            closing: false,
+            paused: false,
+            send_queue: VecDeque::new(),
+            offset: 0,
+            queued: 0,
+            limit: limit,
+            metrics: metrics,
+            proto_reader: ProtoReader::with_max_size(max_frame),
+            connected_at: now_ms(),
+            last_read: now_ms(),
+            partial_since: None,
+            pinged_at: None,
+            timer: None,
         }
     }
 
+    // The earliest moment something has to be done about the connection.
+    pub fn deadline(&self, timeouts: &Timeouts) -> Option<(u64, Deadline)> {
+        let mut deadlines = Vec::new();
+        if timeouts.handshake_ms > 0 && self.login.is_none() {
+            deadlines.push((self.connected_at + timeouts.handshake_ms,
+                            Deadline::Close(format!("no login in {} ms", timeouts.handshake_ms))));
+        }
+        // A paused client is not read from, so its silence proves nothing.
+        if self.paused {
+            return deadlines.into_iter().next();
+        }
+        if let (true, Some(since)) = (timeouts.frame_ms > 0, self.partial_since) {
+            deadlines.push((since + timeouts.frame_ms,
+                            Deadline::Close(format!("incomplete message for {} ms", timeouts.frame_ms))));
+        }
+        if timeouts.idle_ms > 0 {
+            deadlines.push((self.last_read + timeouts.idle_ms,
+                            Deadline::Close(format!("idle for {} ms", timeouts.idle_ms))));
+        }
+        if timeouts.ping_ms > 0 && self.login.is_some() {
+            deadlines.push(match self.pinged_at {
+                None => (self.last_read + timeouts.ping_ms, Deadline::Ping),
+                Some(at) => (at + timeouts.pong_ms,
+                             Deadline::Close(format!("no answer to ping in {} ms", timeouts.pong_ms))),
+            });
+        }
+        deadlines.into_iter().min_by_key(|&(at, _)| at)
+    }
+
     pub fn register<H: Handler>(&mut self, event_loop: &mut mio::EventLoop<H>) -> io::Result<()> {
         self.interest.insert(EventSet::readable());
 
@@ -53,18 +129,106 @@ impl<M: protobuf::MessageStatic> Connection<M> {
         })
     }
 
-    pub fn send_message(&mut self, message: ByteBuf) -> io::Result<()> {
-        self.send_queue.push(message);
+    pub fn send_message(&mut self, frame: Frame) -> io::Result<()> {
+        let len = frame.len();
+        // Only what is already queued counts, so that a message larger than
+        // the limit still goes out, alone.
+        let max_bytes = self.limit.max_bytes;
+        if !self.send_queue.is_empty() && self.queued >= max_bytes {
+            match self.limit.overflow {
+                Overflow::Disconnect => {
+                    self.metrics.overflow_disconnects.fetch_add(1, Ordering::Relaxed);
+                    return Err(io::Error::new(io::ErrorKind::Other, "send queue is full"));
+                }
+                Overflow::DropOldest => {
+                    while self.queued >= max_bytes && self.drop_oldest() {}
+                    if self.queued >= max_bytes {
+                        debug!("Dropping a message of {} bytes for {:?}", len, self.token);
+                        self.metrics.dropped(len);
+                        return Ok(());
+                    }
+                }
+                Overflow::Pause => {
+                    // Pausing only stops this client, others may still fill its queue.
+                    if self.queued >= 2 * max_bytes {
+                        self.metrics.overflow_disconnects.fetch_add(1, Ordering::Relaxed);
+                        return Err(io::Error::new(io::ErrorKind::Other, "send queue is full"));
+                    }
+                    if !self.paused {
+                        debug!("Pausing {:?}, {} bytes queued", self.token, self.queued);
+                        self.paused = true;
+                        self.interest.remove(EventSet::readable());
+                        self.metrics.pauses.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+
+        self.queued += len;
+        self.send_queue.push_back(frame);
         self.interest.insert(EventSet::writable());
         Ok(())
     }
 
+    fn drop_oldest(&mut self) -> bool {
+        // The front frame can't be dropped once it is partially written.
+        let oldest = if self.offset > 0 { 1 } else { 0 };
+        match self.send_queue.remove(oldest) {
+            Some(dropped) => {
+                self.queued -= dropped.len();
+                self.metrics.dropped(dropped.len());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.closing = true;
+        self.interest.remove(EventSet::readable());
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.closing && self.send_queue.is_empty()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Bytes read from the socket, but not yet decoded.
+    pub fn buffered(&self) -> usize {
+        self.proto_reader.buffered()
+    }
+
     pub fn readable(&mut self) -> io::Result<Option<M>> {
-        match self.proto_reader.read(&mut self.socket) {
-            Ok(buf) => Ok(Some(buf)),
-            Err(e) => match e.kind()  {
-                io::ErrorKind::WouldBlock => return Ok(None),
-                _ => return Err(e)
+        if self.closing || self.paused {
+            return Ok(None);
+        }
+        loop {
+            if let Some(msg) = try!(self.proto_reader.next()) {
+                self.partial_since = None;
+                return Ok(Some(msg));
+            }
+            if self.proto_reader.buffered() > 0 && self.partial_since.is_none() {
+                self.partial_since = Some(now_ms());
+            }
+            match self.proto_reader.fill(&mut self.socket) {
+                Ok(0) => {
+                    // The peer is done sending, but may still read what we owe it.
+                    try!(self.proto_reader.eof());
+                    debug!("{:?} closed its side of the connection", self.token);
+                    self.close();
+                    return Ok(None);
+                }
+                Ok(_) => {
+                    self.last_read = now_ms();
+                    self.pinged_at = None;
+                }
+                Err(e) => match e.kind()  {
+                    io::ErrorKind::WouldBlock => return Ok(None),
+                    _ => return Err(e)
+                }
             }
         }
     }
@@ -72,31 +236,53 @@ impl<M: protobuf::MessageStatic> Connection<M> {
     pub fn writable(&mut self) -> io::Result<()> {
         debug!("queue size for {:?} is {}", self.token, self.send_queue.len());
 
-        while let Some(mut buf) = self.send_queue.pop() {
-            match self.socket.try_write_buf(&mut buf) {
-                Ok(None) => {
-                    debug!("client flushing buf");
-                    self.send_queue.push(buf);
-                    break;
-                }
-                Ok(Some(n)) => {
-                    debug!("Wrote {} bytes for {:?}", n, self.token);
-                    if buf.has_remaining() {
-                        self.send_queue.push(buf);
+        loop {
+            let written = match self.send_queue.front() {
+                None => break,
+                Some(frame) => match self.socket.try_write(&frame[self.offset..]) {
+                    Ok(None) => {
+                        debug!("client flushing buf");
                         break;
                     }
-                },
-                Err(e) => {
-                    error!("Failed to send buffer for {:?}, error: {:?}", self.token, e);
-                    return Err(e);
+                    Ok(Some(n)) => {
+                        debug!("Wrote {} bytes for {:?}", n, self.token);
+                        self.offset += n;
+                        self.queued -= n;
+                        self.offset == frame.len()
+                    }
+                    Err(e) => {
+                        error!("Failed to send buffer for {:?}, error: {:?}", self.token, e);
+                        return Err(e);
+                    }
                 }
+            };
+
+            // A partially written frame stays at the front, so that the rest
+            // of it goes out before anything queued after it.
+            if !written {
+                break;
             }
+            self.send_queue.pop_front();
+            self.offset = 0;
         }
 
         if self.send_queue.is_empty() {
             self.interest.remove(EventSet::writable());
         }
 
+        if self.paused && self.queued <= self.limit.max_bytes / 2 {
+            debug!("Resuming {:?}", self.token);
+            self.paused = false;
+            // Count the pause as time the client was alive.
+            let now = now_ms();
+            self.last_read = now;
+            self.pinged_at = None;
+            self.partial_since = self.partial_since.map(|_| now);
+            if !self.closing {
+                self.interest.insert(EventSet::readable());
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,81 +1,183 @@
 use std::io;
-use std::thread;
+use std::net::IpAddr;
+use std::thread::{self, JoinHandle};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use mio::{self, Token, EventSet};
-use mio::buf::{ByteBuf, Buf};
 use mio::tcp::TcpStream;
 use mio::util::Slab;
 use time;
 
 use super::ProtoHandler;
-use super::{User, Sender, WorkerMessage};
+use super::{User, Sender, WorkerMessage, SendLimit, Timeouts, Metrics, Frame, reject};
+use super::rooms::Rooms;
+use super::logins::Logins;
+use super::clients::Clients;
+use super::linger::{self, Lingering};
 
 mod connection;
 mod utils;
 
 use self::utils::*;
-use self::connection::Connection;
+use self::connection::{Connection, Deadline};
+use proto_reader::is_frame_too_large;
 
 pub type Workers<H> = Vec<mio::Sender<WorkerMessage<H>>>;
 
+pub enum Timeout {
+    // Check whether the connection has run out of time.
+    Connection(Token),
+    // Close a refused socket, whether or not the client has hung up.
+    Linger(Token),
+    // Give up on flushing connections of a stopping worker.
+    Shutdown,
+}
+
+struct WhoQuery {
+    token: Token,
+    pending: usize,
+    logins: Vec<String>,
+}
+
 pub struct Worker<H: ProtoHandler> {
     id: usize,
     handler: H,
     connections: Slab<Connection<H::Proto>>,
-    peers: Workers<H>,
+    lingering: Lingering,
+    workers: Workers<H>,
+    rooms: Arc<Mutex<Rooms>>,
+    logins: Arc<Mutex<Logins>>,
+    clients: Arc<Mutex<Clients>>,
+    queries: HashMap<u64, WhoQuery>,
+    next_query: u64,
+    limit: SendLimit,
+    max_frame: usize,
+    timeouts: Timeouts,
+    ping: Frame,
+    metrics: Arc<Metrics>,
+    // Connections to reset once the one being reset is done, see `reset_connection`.
+    resets: Vec<Token>,
+    resetting: bool,
+    // Set once the server is shutting down, the loop ends with the last connection.
+    stopping: bool,
     loop_end: u64
 }
 
 impl<H: ProtoHandler> Worker<H> {
 
-    fn new(id: usize, handler: H, peers: Workers<H>) -> Self {
+    fn new(id: usize,
+           handler: H,
+           workers: Workers<H>,
+           rooms: Arc<Mutex<Rooms>>,
+           logins: Arc<Mutex<Logins>>,
+           clients: Arc<Mutex<Clients>>,
+           limit: SendLimit,
+           max_frame: usize,
+           timeouts: Timeouts,
+           capacity: usize,
+           metrics: Arc<Metrics>) -> Self {
         assert!(id > 0);
+        let ping = to_frame(&handler.ping());
         Worker {
             id: id,
             handler: handler,
-            connections: Slab::new_starting_at(Token(100 * id), 100_000),
-            peers: peers,
+            connections: Slab::new_starting_at(Token(100 * id), capacity),
+            lingering: Lingering::new(Token(100 * id + capacity)),
+            workers: workers,
+            rooms: rooms,
+            logins: logins,
+            clients: clients,
+            queries: HashMap::new(),
+            next_query: 0,
+            limit: limit,
+            max_frame: max_frame,
+            timeouts: timeouts,
+            ping: ping,
+            metrics: metrics,
+            resets: Vec::new(),
+            resetting: false,
+            stopping: false,
             loop_end: 0,
         }
     }
 
-    pub fn start(handler: H, n_workers: usize) -> Workers<H> {
+    pub fn start(handler: H,
+                 n_workers: usize,
+                 limit: SendLimit,
+                 max_frame: usize,
+                 timeouts: Timeouts,
+                 max_connections: usize,
+                 clients: Arc<Mutex<Clients>>,
+                 metrics: Arc<Metrics>) -> io::Result<(Workers<H>, Vec<JoinHandle<()>>)> {
         assert!(n_workers > 0, "Need at least one worker");
-        let loops = (0..n_workers)
-        .map(|_| mio::EventLoop::<Self>::new().ok().expect("Failed to crate a worker"))
-        .collect::<Vec<_>>();
+        // Round robin does not keep workers balanced as clients leave, so any
+        // worker may end up with all of the connections.
+        let capacity = if max_connections > 0 { max_connections } else { 100_000 };
+        // A timer per connection and lingering socket, and one for the
+        // shutdown deadline.
+        let config = mio::EventLoopConfig {
+            timer_capacity: capacity + linger::CAPACITY + 1,
+            ..mio::EventLoopConfig::default()
+        };
+        let loops = try!((0..n_workers)
+        .map(|_| mio::EventLoop::<Self>::configured(config.clone()))
+        .collect::<io::Result<Vec<_>>>());
         let chans = loops.iter().map(|l| l.channel()).collect::<Vec<_>>();
+        let rooms = Arc::new(Mutex::new(Rooms::new()));
+        let logins = Arc::new(Mutex::new(Logins::new()));
 
+        let mut threads = Vec::new();
         for (id, mut l) in loops.into_iter().enumerate() {
-            let peers = (0..n_workers)
-            .filter(|&i| i != id).map(|i| chans[i].clone()).collect();
+            let workers = chans.clone();
             let handler = handler.clone();
-
-            thread::spawn(move || {
-                l.run(&mut Worker::new(id + 1, handler, peers))
+            let rooms = rooms.clone();
+            let logins = logins.clone();
+            let clients = clients.clone();
+            let metrics = metrics.clone();
+
+            threads.push(thread::spawn(move || {
+                l.run(&mut Worker::new(id + 1, handler, workers, rooms, logins, clients,
+                                       limit, max_frame, timeouts, capacity, metrics))
                 .ok().expect("Failed to start a worker event loop");
-            });
+            }));
 
         }
 
-        chans
+        Ok((chans, threads))
     }
 
-    fn accept(&mut self, event_loop: &mut mio::EventLoop<Self>, sock: TcpStream) {
+    fn accept(&mut self, event_loop: &mut mio::EventLoop<Self>, sock: TcpStream, ip: IpAddr) {
         sock.set_nodelay(true).unwrap_or_else(|e|
                                               error!("Failed to set nodelay, {:?}", e));
 
-        match self.connections.insert_with(|token| Connection::new(sock, token)) {
+        if !self.connections.has_remaining() {
+            warn!("Worker {} is full, refusing connection from {}", self.id, ip);
+            self.clients.lock().unwrap().release(ip);
+            let sock = reject(&self.handler, sock, "too many connections".to_string(), &self.metrics);
+            self.lingering.add(event_loop, sock, Timeout::Linger);
+            return;
+        }
+
+        let limit = self.limit;
+        let max_frame = self.max_frame;
+        let metrics = self.metrics.clone();
+        let connection = |token| Connection::new(sock, token, ip, limit, max_frame, metrics);
+        match self.connections.insert_with(connection) {
             Some(token) => {
                 match self.connections[token].register(event_loop) {
-                    Ok(_) => {},
+                    Ok(_) => self.schedule(event_loop, token),
                     Err(e) => {
                         error!("Failed to register connection, {:?}", e);
                         self.connections.remove(token);
+                        self.clients.lock().unwrap().release(ip);
                     }
                 }
             }
-            None => error!("Failed to insert connection into slab")
+            None => {
+                error!("Failed to insert connection into slab");
+                self.clients.lock().unwrap().release(ip);
+            }
         }
     }
 
@@ -84,51 +186,382 @@ impl<H: ProtoHandler> Worker<H> {
                 token: Token)
                 -> io::Result<()> {
 
-        while let Some(msg) = try!(self.connections[token].readable()) {
-            let mut user = User::new(token, event_loop.channel());
-            self.handler.recv(&mut user, msg);
+        loop {
+            let msg = match self.connections[token].readable() {
+                Ok(Some(msg)) => msg,
+                Ok(None) => break,
+                Err(ref e) if is_frame_too_large(e) => {
+                    warn!("Closing {:?}: {}", token, e);
+                    let mut user = self.user(event_loop, token);
+                    self.handler.protocol_error(&mut user, e.to_string());
+                    user.disconnect();
+                    self.perform_requests(event_loop, token, user);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let mut user = self.user(event_loop, token);
+            if user.login().is_some() {
+                self.handler.recv(&mut user, msg);
+            } else {
+                self.handler.handshake(&mut user, msg);
+                if user.login().is_none() {
+                    user.disconnect();
+                }
+            }
             self.perform_requests(event_loop, token, user);
+
+            if !self.connections.contains(token) {
+                break;
+            }
         }
         Ok(())
     }
 
+    fn user(&self, event_loop: &mio::EventLoop<Self>, token: Token) -> User<H> {
+        User::new(self.id,
+                  token,
+                  self.connections.get(token).and_then(|conn| conn.login.clone()),
+                  event_loop.channel(),
+                  self.rooms.clone(),
+                  self.logins.clone())
+    }
+
     fn perform_requests(&mut self,
                         event_loop: &mut mio::EventLoop<Self>,
                         token: Token,
                         user: User<H>) {
-        let  User {broadcast, echo, ..} = user;
+        let  User {new_login, broadcast, multicast, direct, echo, who, disconnect, ..} = user;
 
-        if let Some(proto) = broadcast {
+        if let Some(login) = new_login {
+            self.set_login(token, login);
+        }
+
+        for proto in broadcast {
             self.broadcast(event_loop, proto);
         }
 
-        if let Some(proto) = echo {
-            let buf = to_buf(&proto);
-            self.connections[token].send_message(buf)
+        for (room, proto) in multicast {
+            self.multicast(event_loop, &room, proto);
+        }
+
+        for (login, proto) in direct {
+            self.direct(event_loop, &login, proto);
+        }
+
+        for proto in echo {
+            if !self.connections.contains(token) {
+                break;
+            }
+            let frame = to_frame(&proto);
+            self.connections[token].send_message(frame)
                 .and_then(|_| self.connections[token].reregister(event_loop))
-                .unwrap_or_else(|_| self.reset_connection(token));
+                .unwrap_or_else(|_| self.reset_connection(event_loop, token));
+        }
+
+        if who {
+            self.who(event_loop, token);
+        }
+
+        if disconnect {
+            if let Some(conn) = self.connections.get_mut(token) {
+                conn.close();
+            }
+        }
+
+        if self.connections.get(token).map_or(false, |conn| conn.is_finished()) {
+            self.reset_connection(event_loop, token);
         }
     }
 
-    fn reset_connection(&mut self, token: Token) {
+    // The login is already registered by `User::set_login`.
+    fn set_login(&mut self, token: Token, login: String) {
+        match self.connections.get_mut(token) {
+            Some(conn) => conn.login = Some(login),
+            None => self.logins.lock().unwrap().unregister(&login, (self.id, token)),
+        }
+    }
+
+    // Telling the others that a user left may overflow their queues and reset
+    // them too, so resets made meanwhile are queued rather than nested.
+    fn reset_connection(&mut self, event_loop: &mut mio::EventLoop<Self>, token: Token) {
+        self.resets.push(token);
+        if self.resetting {
+            return;
+        }
+        self.resetting = true;
+        while let Some(token) = self.resets.pop() {
+            self.close_connection(event_loop, token);
+        }
+        self.resetting = false;
+    }
+
+    fn close_connection(&mut self, event_loop: &mut mio::EventLoop<Self>, token: Token) {
         info!("reset connection {:?}", token);
-        self.connections.remove(token);
+        let mut user = self.user(event_loop, token);
+        self.rooms.lock().unwrap().leave_all((self.id, token));
+        let conn = match self.connections.remove(token) {
+            Some(conn) => conn,
+            None => return,
+        };
+        if let Some((_, timer)) = conn.timer {
+            event_loop.clear_timeout(timer);
+        }
+        self.clients.lock().unwrap().release(conn.peer);
+
+        if let Some(ref login) = conn.login {
+            self.logins.lock().unwrap().unregister(login, (self.id, token));
+            // Everyone is leaving, no need to tell the others.
+            if !self.stopping {
+                self.handler.disconnected(&mut user);
+                self.perform_requests(event_loop, token, user);
+            }
+        }
+
+        if self.stopping && self.connections.is_empty() {
+            info!("Worker {} flushed all connections", self.id);
+            event_loop.shutdown();
+        }
+    }
+
+    // Makes sure a timer fires no later than the deadline of the connection.
+    // Timers are not moved forward as the client shows signs of life, instead
+    // `connection_timeout` finds out the deadline has moved and sets a new one.
+    fn schedule(&mut self, event_loop: &mut mio::EventLoop<Self>, token: Token) {
+        let conn = match self.connections.get_mut(token) {
+            Some(conn) => conn,
+            None => return,
+        };
+        let deadline = match conn.deadline(&self.timeouts) {
+            Some((at, _)) => at,
+            None => return,
+        };
+        if let Some((at, timer)) = conn.timer {
+            if at <= deadline {
+                return;
+            }
+            event_loop.clear_timeout(timer);
+            conn.timer = None;
+        }
+
+        let delay = deadline.saturating_sub(now_ms());
+        match event_loop.timeout_ms(Timeout::Connection(token), delay) {
+            Ok(timer) => conn.timer = Some((deadline, timer)),
+            Err(e) => error!("Failed to set a timer for {:?}, {:?}", token, e),
+        }
+    }
+
+    fn connection_timeout(&mut self, event_loop: &mut mio::EventLoop<Self>, token: Token) {
+        let expired = match self.connections.get_mut(token) {
+            Some(conn) => {
+                conn.timer = None;
+                match conn.deadline(&self.timeouts) {
+                    Some((at, deadline)) if at <= now_ms() => Some(deadline),
+                    _ => None,
+                }
+            }
+            None => return,
+        };
+
+        match expired {
+            Some(Deadline::Close(reason)) => {
+                info!("Closing {:?}: {}", token, reason);
+                self.reset_connection(event_loop, token);
+                return;
+            }
+            Some(Deadline::Ping) => {
+                debug!("Pinging {:?}", token);
+                let sent = {
+                    let conn = &mut self.connections[token];
+                    conn.pinged_at = Some(now_ms());
+                    conn.send_message(self.ping.clone()).and_then(|_| conn.reregister(event_loop))
+                };
+                if let Err(e) = sent {
+                    error!("Failed to ping {:?}: {:?}", token, e);
+                    self.reset_connection(event_loop, token);
+                    return;
+                }
+            }
+            None => {}
+        }
+        self.schedule(event_loop, token);
+    }
+
+    fn shutdown(&mut self,
+                event_loop: &mut mio::EventLoop<Self>,
+                goodbye: Option<Frame>,
+                deadline_ms: u64) {
+        info!("Worker {} is shutting down, {} connections open", self.id, self.connections.count());
+        self.stopping = true;
+        if self.connections.is_empty() {
+            event_loop.shutdown();
+            return;
+        }
+
+        if let Err(e) = event_loop.timeout_ms(Timeout::Shutdown, deadline_ms) {
+            error!("Failed to set shutdown deadline, {:?}", e);
+            event_loop.shutdown();
+            return;
+        }
+
+        let mut bad_tokens = Vec::new();
+        for conn in self.connections.iter_mut() {
+            conn.close();
+            let sent = match goodbye {
+                Some(ref frame) => conn.send_message(frame.clone()),
+                None => Ok(()),
+            };
+            sent.and_then(|_| conn.reregister(event_loop))
+                .unwrap_or_else(|e| {
+                    error!("Failed to say goodbye to {:?}: {:?}", conn.token, e);
+                    bad_tokens.push(conn.token);
+                });
+            if conn.is_finished() {
+                bad_tokens.push(conn.token);
+            }
+        }
+
+        for t in bad_tokens {
+            self.reset_connection(event_loop, t);
+        }
+    }
+
+    fn local_logins(&self) -> Vec<String> {
+        self.connections.iter().filter_map(|conn| conn.login.clone()).collect()
+    }
+
+    fn who(&mut self, event_loop: &mut mio::EventLoop<Self>, token: Token) {
+        let query = self.next_query;
+        self.next_query += 1;
+
+        let mut pending = 0;
+        let id = self.id;
+        for (_, p) in self.workers.iter().enumerate().filter(|&(i, _)| i + 1 != id) {
+            match p.send(WorkerMessage::WhoRequest(id, query)) {
+                Ok(()) => pending += 1,
+                Err(e) => error!("cannot ask peer for online users, {:?}", e),
+            }
+        }
+
+        let logins = self.local_logins();
+        self.queries.insert(query, WhoQuery { token: token, pending: pending, logins: logins });
+        if pending == 0 {
+            self.finish_who(event_loop, query);
+        }
+    }
+
+    fn who_response(&mut self,
+                    event_loop: &mut mio::EventLoop<Self>,
+                    query: u64,
+                    logins: Vec<String>) {
+        let done = match self.queries.get_mut(&query) {
+            Some(q) => {
+                q.logins.extend(logins);
+                q.pending -= 1;
+                q.pending == 0
+            }
+            None => {
+                warn!("Response for unknown who query {}", query);
+                return;
+            }
+        };
+
+        if done {
+            self.finish_who(event_loop, query);
+        }
+    }
+
+    fn finish_who(&mut self, event_loop: &mut mio::EventLoop<Self>, query: u64) {
+        let WhoQuery { token, logins, .. } = self.queries.remove(&query)
+            .expect("[BUG]: Finishing unknown who query");
+        if !self.connections.contains(token) {
+            return;
+        }
+        let mut user = self.user(event_loop, token);
+        self.handler.users_online(&mut user, logins);
+        self.perform_requests(event_loop, token, user);
     }
 
     fn broadcast(&mut self, event_loop: &mut mio::EventLoop<Self>, proto: H::Proto) {
-        for p in self.peers.iter() {
-            if let Err(e) = p.send(WorkerMessage::Broadcast(proto.clone())) {
+        let frame = to_frame(&proto);
+        let id = self.id;
+        for (_, p) in self.workers.iter().enumerate().filter(|&(i, _)| i + 1 != id) {
+            if let Err(e) = p.send(WorkerMessage::Broadcast(frame.clone())) {
+                error!("cannot forward post to peer, {:?}", e);
+            }
+        }
+        self.broadcast_local(event_loop, frame)
+    }
+
+    fn multicast(&mut self, event_loop: &mut mio::EventLoop<Self>, room: &str, proto: H::Proto) {
+        let frame = to_frame(&proto);
+        let mut by_worker = vec![Vec::new(); self.workers.len()];
+        for (worker, token) in self.rooms.lock().unwrap().members(room) {
+            by_worker[worker - 1].push(token);
+        }
+
+        let mut local = Vec::new();
+        for (i, tokens) in by_worker.into_iter().enumerate() {
+            if tokens.is_empty() {
+                continue;
+            }
+            if i + 1 == self.id {
+                local = tokens;
+                continue;
+            }
+            if let Err(e) = self.workers[i].send(WorkerMessage::Multicast(room.to_string(), tokens, frame.clone())) {
+                error!("cannot forward post to peer, {:?}", e);
+            }
+        }
+        self.deliver_local(event_loop, &local, frame)
+    }
+
+    fn direct(&mut self, event_loop: &mut mio::EventLoop<Self>, login: &str, proto: H::Proto) {
+        let frame = to_frame(&proto);
+        let mut local = Vec::new();
+        for (worker, token) in self.logins.lock().unwrap().lookup(login) {
+            if worker == self.id {
+                local.push(token);
+                continue;
+            }
+            if let Err(e) = self.workers[worker - 1].send(WorkerMessage::Direct(login.to_string(), token, frame.clone())) {
                 error!("cannot forward post to peer, {:?}", e);
             }
         }
-        self.broadcast_local(event_loop, proto)
+        self.deliver_local(event_loop, &local, frame)
     }
 
-    fn broadcast_local(&mut self, event_loop: &mut mio::EventLoop<Self>, proto: H::Proto) {
-        let buf = to_buf(&proto);
+    fn deliver_local(&mut self,
+                    event_loop: &mut mio::EventLoop<Self>,
+                    tokens: &[Token],
+                    frame: Frame) {
         let mut bad_tokens = Vec::new();
-        for conn in self.connections.iter_mut() {
-            conn.send_message(ByteBuf::from_slice(buf.bytes()))
+        for &token in tokens {
+            let conn = match self.connections.get_mut(token) {
+                Some(conn) => conn,
+                None => {
+                    debug!("{:?} is gone, skipping", token);
+                    continue;
+                }
+            };
+            conn.send_message(frame.clone())
+                .and_then(|_| conn.reregister(event_loop))
+                .unwrap_or_else(|e| {
+                    error!("Failed to send message for {:?}: {:?}", token, e);
+                    bad_tokens.push(token);
+                });
+        }
+
+        for t in bad_tokens {
+            self.reset_connection(event_loop, t);
+        }
+    }
+
+    fn broadcast_local(&mut self, event_loop: &mut mio::EventLoop<Self>, frame: Frame) {
+        let mut bad_tokens = Vec::new();
+        for conn in self.connections.iter_mut().filter(|conn| conn.login.is_some()) {
+            conn.send_message(frame.clone())
                 .and_then(|_| conn.reregister(event_loop))
                 .unwrap_or_else(|e| {
                     error!("Failed to send message for {:?}: {:?}", conn.token, e);
@@ -137,14 +570,14 @@ impl<H: ProtoHandler> Worker<H> {
         }
 
         for t in bad_tokens {
-            self.reset_connection(t);
+            self.reset_connection(event_loop, t);
         }
     }
 }
 
 
 impl<H: ProtoHandler> mio::Handler for Worker<H> {
-    type Timeout = ();
+    type Timeout = Timeout;
     type Message = WorkerMessage<H>;
 
     fn ready(&mut self,
@@ -156,6 +589,16 @@ impl<H: ProtoHandler> mio::Handler for Worker<H> {
         debug!("events = {:?}", events);
         assert!(token != Token(0), "[BUG]: Received event for Token(0)");
 
+        if self.lingering.contains(token) {
+            self.lingering.ready(event_loop, token);
+            return;
+        }
+
+        if !self.connections.contains(token) {
+            debug!("Event for a closed connection {:?}", token);
+            return;
+        }
+
         if events.is_error() || events.is_hup() {
             if events.is_error() {
                 error!("Error event for {:?}", token);
@@ -163,30 +606,53 @@ impl<H: ProtoHandler> mio::Handler for Worker<H> {
                 warn!("Hup event for {:?}", token);
             }
 
-            self.reset_connection(token);
+            self.reset_connection(event_loop, token);
             return;
         }
 
+        let mut events = events;
         if events.is_writable() {
             trace!("Write event for {:?}", token);
 
+            let paused = self.connections[token].is_paused();
             self.connections[token].writable()
             .and_then(|_| self.connections[token].reregister(event_loop))
             .unwrap_or_else(|e| {
                 error!("Write event failed for {:?}, {:?}", token, e);
-                self.reset_connection(token);
+                self.reset_connection(event_loop, token);
             });
+
+            if self.connections.get(token).map_or(false, |conn| conn.is_finished()) {
+                self.reset_connection(event_loop, token);
+            }
+
+            // Frames that arrived before the pause are already read from the
+            // socket, so no read event is coming for them.
+            let resumed = self.connections.get(token)
+                .map_or(false, |conn| !conn.is_paused() && conn.buffered() > 0);
+            if paused && resumed {
+                events.insert(EventSet::readable());
+            }
         }
 
-        if events.is_readable() {
+        if events.is_readable() && self.connections.contains(token) {
             trace!("Read event for {:?}", token);
             self.readable(event_loop, token)
-            .and_then(|_| self.connections[token].reregister(event_loop))
+            .and_then(|_| match self.connections.get_mut(token) {
+                Some(conn) => conn.reregister(event_loop),
+                None => Ok(()),
+            })
             .unwrap_or_else(|e| {
                 error!("Read event failed for {:?}: {:?}", token, e);
-                self.reset_connection(token);
+                self.reset_connection(event_loop, token);
             });
+
+            if self.connections.get(token).map_or(false, |conn| conn.is_finished()) {
+                self.reset_connection(event_loop, token);
+            }
         }
+
+        self.schedule(event_loop, token);
         let end = time::precise_time_ns();
         self.loop_end = end;
         debug!("loop duration: {} ns", end - start);
@@ -195,16 +661,62 @@ impl<H: ProtoHandler> mio::Handler for Worker<H> {
     fn notify(&mut self, event_loop: &mut mio::EventLoop<Self>, msg: Self::Message) {
         info!("Worker {} received a message", self.id);
         match msg {
-            WorkerMessage::NewConnection(sock) => {
-                self.accept(event_loop, sock)
+            WorkerMessage::NewConnection(sock, ip) => {
+                self.accept(event_loop, sock, ip)
             }
-            WorkerMessage::HandlerMessage(token, m) => {
-                let mut user = User::new(token, event_loop.channel());
+            WorkerMessage::HandlerMessage(login, token, m) => {
+                if self.connections.get(token).map(|conn| &conn.login) != Some(&login) {
+                    debug!("{:?} is no longer at {:?}, dropping a handler message", login, token);
+                    return;
+                }
+                let mut user = self.user(event_loop, token);
                 self.handler.notify(&mut user, m);
                 self.perform_requests(event_loop, token, user);
             }
-            WorkerMessage::Broadcast(post) => {
-                self.broadcast_local(event_loop, post)
+            WorkerMessage::Broadcast(frame) => {
+                self.broadcast_local(event_loop, frame)
+            }
+            WorkerMessage::Multicast(room, tokens, frame) => {
+                // A token of a member who left may already belong to someone else.
+                let tokens = {
+                    let rooms = self.rooms.lock().unwrap();
+                    tokens.into_iter()
+                        .filter(|&token| rooms.is_member(&room, (self.id, token)))
+                        .collect::<Vec<_>>()
+                };
+                self.deliver_local(event_loop, &tokens, frame)
+            }
+            WorkerMessage::Direct(login, token, frame) => {
+                // The recipient may have left and the token may belong to
+                // someone else by now.
+                if self.connections.get(token).and_then(|conn| conn.login.as_ref()) == Some(&login) {
+                    self.deliver_local(event_loop, &[token], frame)
+                } else {
+                    debug!("{} is no longer at {:?}, dropping a direct message", login, token);
+                }
+            }
+            WorkerMessage::WhoRequest(worker, query) => {
+                let logins = self.local_logins();
+                if let Err(e) = self.workers[worker - 1].send(WorkerMessage::WhoResponse(query, logins)) {
+                    error!("cannot send online users to peer, {:?}", e);
+                }
+            }
+            WorkerMessage::WhoResponse(query, logins) => {
+                self.who_response(event_loop, query, logins)
+            }
+            WorkerMessage::Shutdown(goodbye, deadline_ms) => {
+                self.shutdown(event_loop, goodbye, deadline_ms)
+            }
+        }
+    }
+
+    fn timeout(&mut self, event_loop: &mut mio::EventLoop<Self>, timeout: Timeout) {
+        match timeout {
+            Timeout::Connection(token) => self.connection_timeout(event_loop, token),
+            Timeout::Linger(token) => self.lingering.expire(token),
+            Timeout::Shutdown => {
+                warn!("Worker {} gave up on flushing {} connections", self.id, self.connections.count());
+                event_loop.shutdown();
             }
         }
     }
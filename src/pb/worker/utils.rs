@@ -1,11 +1,17 @@
-use mio::buf::ByteBuf;
+use std::sync::Arc;
+
 use protobuf;
+use time;
+
+use codec;
+use pb::Frame;
 
 
-pub fn to_buf<P: protobuf::Message>(msg: &P) -> ByteBuf {
-    let size = msg.compute_size();
-    let mut buf = ByteBuf::mut_with_capacity((size + 5) as usize);
-    msg.write_length_delimited_to_writer(&mut buf).unwrap();
-    buf.flip()
+pub fn now_ms() -> u64 {
+    time::precise_time_ns() / 1_000_000
+}
+
+pub fn to_frame<P: protobuf::Message>(msg: &P) -> Frame {
+    Arc::new(codec::encode(msg))
 }
 
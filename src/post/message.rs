@@ -16,6 +16,13 @@ pub struct Message {
     field_type: ::std::option::Option<Message_Type>,
     text: ::protobuf::RepeatedField<::std::string::String>,
     author: ::protobuf::SingularField<::std::string::String>,
+    room: ::protobuf::SingularField<::std::string::String>,
+    recipient: ::protobuf::SingularField<::std::string::String>,
+    password: ::protobuf::SingularField<::std::string::String>,
+    id: ::std::option::Option<u64>,
+    since: ::std::option::Option<u64>,
+    limit: ::std::option::Option<u32>,
+    timestamp: ::std::option::Option<i64>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::std::cell::Cell<u32>,
@@ -37,6 +44,13 @@ impl Message {
                     field_type: ::std::option::Option::None,
                     text: ::protobuf::RepeatedField::new(),
                     author: ::protobuf::SingularField::none(),
+                    room: ::protobuf::SingularField::none(),
+                    recipient: ::protobuf::SingularField::none(),
+                    password: ::protobuf::SingularField::none(),
+                    id: ::std::option::Option::None,
+                    since: ::std::option::Option::None,
+                    limit: ::std::option::Option::None,
+                    timestamp: ::std::option::Option::None,
                     unknown_fields: ::protobuf::UnknownFields::new(),
                     cached_size: ::std::cell::Cell::new(0),
                 }
@@ -123,6 +137,190 @@ impl Message {
             None => "",
         }
     }
+
+    // optional string room = 5;
+
+    pub fn clear_room(&mut self) {
+        self.room.clear();
+    }
+
+    pub fn has_room(&self) -> bool {
+        self.room.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_room(&mut self, v: ::std::string::String) {
+        self.room = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_room<'a>(&'a mut self) -> &'a mut ::std::string::String {
+        if self.room.is_none() {
+            self.room.set_default();
+        };
+        self.room.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_room(&mut self) -> ::std::string::String {
+        self.room.take().unwrap_or_else(|| ::std::string::String::new())
+    }
+
+    pub fn get_room<'a>(&'a self) -> &'a str {
+        match self.room.as_ref() {
+            Some(v) => &v,
+            None => "",
+        }
+    }
+
+    // optional string recipient = 6;
+
+    pub fn clear_recipient(&mut self) {
+        self.recipient.clear();
+    }
+
+    pub fn has_recipient(&self) -> bool {
+        self.recipient.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_recipient(&mut self, v: ::std::string::String) {
+        self.recipient = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_recipient<'a>(&'a mut self) -> &'a mut ::std::string::String {
+        if self.recipient.is_none() {
+            self.recipient.set_default();
+        };
+        self.recipient.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_recipient(&mut self) -> ::std::string::String {
+        self.recipient.take().unwrap_or_else(|| ::std::string::String::new())
+    }
+
+    pub fn get_recipient<'a>(&'a self) -> &'a str {
+        match self.recipient.as_ref() {
+            Some(v) => &v,
+            None => "",
+        }
+    }
+
+    // optional string password = 7;
+
+    pub fn clear_password(&mut self) {
+        self.password.clear();
+    }
+
+    pub fn has_password(&self) -> bool {
+        self.password.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_password(&mut self, v: ::std::string::String) {
+        self.password = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_password<'a>(&'a mut self) -> &'a mut ::std::string::String {
+        if self.password.is_none() {
+            self.password.set_default();
+        };
+        self.password.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_password(&mut self) -> ::std::string::String {
+        self.password.take().unwrap_or_else(|| ::std::string::String::new())
+    }
+
+    pub fn get_password<'a>(&'a self) -> &'a str {
+        match self.password.as_ref() {
+            Some(v) => &v,
+            None => "",
+        }
+    }
+
+    // optional uint64 id = 8;
+
+    pub fn clear_id(&mut self) {
+        self.id = ::std::option::Option::None;
+    }
+
+    pub fn has_id(&self) -> bool {
+        self.id.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: u64) {
+        self.id = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_id<'a>(&self) -> u64 {
+        self.id.unwrap_or(0)
+    }
+
+    // optional uint64 since = 9;
+
+    pub fn clear_since(&mut self) {
+        self.since = ::std::option::Option::None;
+    }
+
+    pub fn has_since(&self) -> bool {
+        self.since.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_since(&mut self, v: u64) {
+        self.since = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_since<'a>(&self) -> u64 {
+        self.since.unwrap_or(0)
+    }
+
+    // optional uint32 limit = 10;
+
+    pub fn clear_limit(&mut self) {
+        self.limit = ::std::option::Option::None;
+    }
+
+    pub fn has_limit(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_limit(&mut self, v: u32) {
+        self.limit = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_limit<'a>(&self) -> u32 {
+        self.limit.unwrap_or(0)
+    }
+
+    // optional int64 timestamp = 11;
+
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = ::std::option::Option::None;
+    }
+
+    pub fn has_timestamp(&self) -> bool {
+        self.timestamp.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: i64) {
+        self.timestamp = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_timestamp<'a>(&self) -> i64 {
+        self.timestamp.unwrap_or(0)
+    }
 }
 
 impl ::protobuf::Message for Message {
@@ -154,6 +352,55 @@ impl ::protobuf::Message for Message {
                     let tmp = self.author.set_default();
                     try!(is.read_string_into(tmp))
                 },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = self.room.set_default();
+                    try!(is.read_string_into(tmp))
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = self.recipient.set_default();
+                    try!(is.read_string_into(tmp))
+                },
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = self.password.set_default();
+                    try!(is.read_string_into(tmp))
+                },
+                8 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint64());
+                    self.id = ::std::option::Option::Some(tmp);
+                },
+                9 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint64());
+                    self.since = ::std::option::Option::Some(tmp);
+                },
+                10 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_uint32());
+                    self.limit = ::std::option::Option::Some(tmp);
+                },
+                11 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::ProtobufError::WireError("unexpected wire type".to_string()));
+                    };
+                    let tmp = try!(is.read_int64());
+                    self.timestamp = ::std::option::Option::Some(tmp);
+                },
                 _ => {
                     let unknown = try!(is.read_unknown(wire_type));
                     self.mut_unknown_fields().add_value(field_number, unknown);
@@ -176,6 +423,27 @@ impl ::protobuf::Message for Message {
         for value in self.author.iter() {
             my_size += ::protobuf::rt::string_size(4, &value);
         };
+        for value in self.room.iter() {
+            my_size += ::protobuf::rt::string_size(5, &value);
+        };
+        for value in self.recipient.iter() {
+            my_size += ::protobuf::rt::string_size(6, &value);
+        };
+        for value in self.password.iter() {
+            my_size += ::protobuf::rt::string_size(7, &value);
+        };
+        for value in self.id.iter() {
+            my_size += ::protobuf::rt::value_size(8, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.since.iter() {
+            my_size += ::protobuf::rt::value_size(9, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.limit.iter() {
+            my_size += ::protobuf::rt::value_size(10, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        for value in self.timestamp.iter() {
+            my_size += ::protobuf::rt::value_size(11, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -191,6 +459,27 @@ impl ::protobuf::Message for Message {
         if let Some(v) = self.author.as_ref() {
             try!(os.write_string(4, &v));
         };
+        if let Some(v) = self.room.as_ref() {
+            try!(os.write_string(5, &v));
+        };
+        if let Some(v) = self.recipient.as_ref() {
+            try!(os.write_string(6, &v));
+        };
+        if let Some(v) = self.password.as_ref() {
+            try!(os.write_string(7, &v));
+        };
+        if let Some(v) = self.id {
+            try!(os.write_uint64(8, v));
+        };
+        if let Some(v) = self.since {
+            try!(os.write_uint64(9, v));
+        };
+        if let Some(v) = self.limit {
+            try!(os.write_uint32(10, v));
+        };
+        if let Some(v) = self.timestamp {
+            try!(os.write_int64(11, v));
+        };
         try!(os.write_unknown_fields(self.get_unknown_fields()));
         ::std::result::Result::Ok(())
     }
@@ -247,6 +536,41 @@ impl ::protobuf::MessageStatic for Message {
                     Message::has_author,
                     Message::get_author,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_singular_string_accessor(
+                    "room",
+                    Message::has_room,
+                    Message::get_room,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_string_accessor(
+                    "recipient",
+                    Message::has_recipient,
+                    Message::get_recipient,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_string_accessor(
+                    "password",
+                    Message::has_password,
+                    Message::get_password,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u64_accessor(
+                    "id",
+                    Message::has_id,
+                    Message::get_id,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u64_accessor(
+                    "since",
+                    Message::has_since,
+                    Message::get_since,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_u32_accessor(
+                    "limit",
+                    Message::has_limit,
+                    Message::get_limit,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_i64_accessor(
+                    "timestamp",
+                    Message::has_timestamp,
+                    Message::get_timestamp,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Message>(
                     "Message",
                     fields,
@@ -262,6 +586,13 @@ impl ::protobuf::Clear for Message {
         self.clear_field_type();
         self.clear_text();
         self.clear_author();
+        self.clear_room();
+        self.clear_recipient();
+        self.clear_password();
+        self.clear_id();
+        self.clear_since();
+        self.clear_limit();
+        self.clear_timestamp();
         self.unknown_fields.clear();
     }
 }
@@ -271,6 +602,13 @@ impl ::std::cmp::PartialEq for Message {
         self.field_type == other.field_type &&
         self.text == other.text &&
         self.author == other.author &&
+        self.room == other.room &&
+        self.recipient == other.recipient &&
+        self.password == other.password &&
+        self.id == other.id &&
+        self.since == other.since &&
+        self.limit == other.limit &&
+        self.timestamp == other.timestamp &&
         self.unknown_fields == other.unknown_fields
     }
 }
@@ -285,6 +623,15 @@ impl ::std::fmt::Debug for Message {
 pub enum Message_Type {
     COMMAND = 1,
     MESSAGE = 2,
+    JOIN = 3,
+    LEAVE = 4,
+    ROOMS = 5,
+    LOGIN = 6,
+    WHO = 7,
+    HISTORY = 8,
+    PING = 9,
+    PONG = 10,
+    REJECTED = 11,
 }
 
 impl ::protobuf::ProtobufEnum for Message_Type {
@@ -296,6 +643,15 @@ impl ::protobuf::ProtobufEnum for Message_Type {
         match value {
             1 => ::std::option::Option::Some(Message_Type::COMMAND),
             2 => ::std::option::Option::Some(Message_Type::MESSAGE),
+            3 => ::std::option::Option::Some(Message_Type::JOIN),
+            4 => ::std::option::Option::Some(Message_Type::LEAVE),
+            5 => ::std::option::Option::Some(Message_Type::ROOMS),
+            6 => ::std::option::Option::Some(Message_Type::LOGIN),
+            7 => ::std::option::Option::Some(Message_Type::WHO),
+            8 => ::std::option::Option::Some(Message_Type::HISTORY),
+            9 => ::std::option::Option::Some(Message_Type::PING),
+            10 => ::std::option::Option::Some(Message_Type::PONG),
+            11 => ::std::option::Option::Some(Message_Type::REJECTED),
             _ => ::std::option::Option::None
         }
     }
@@ -320,53 +676,181 @@ static file_descriptor_proto_data: &'static [u8] = &[
     0x0a, 0x0d, 0x6d, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12,
     0x1e, 0x72, 0x75, 0x2e, 0x73, 0x70, 0x62, 0x61, 0x75, 0x2e, 0x63, 0x68, 0x61, 0x74, 0x2e, 0x63,
     0x6f, 0x6d, 0x6d, 0x6f, 0x6e, 0x73, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x63, 0x6f, 0x6c, 0x22,
-    0x85, 0x01, 0x0a, 0x07, 0x4d, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x12, 0x3a, 0x0a, 0x04, 0x74,
+    0xd9, 0x02, 0x0a, 0x07, 0x4d, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x12, 0x3a, 0x0a, 0x04, 0x74,
     0x79, 0x70, 0x65, 0x18, 0x02, 0x20, 0x02, 0x28, 0x0e, 0x32, 0x2c, 0x2e, 0x72, 0x75, 0x2e, 0x73,
     0x70, 0x62, 0x61, 0x75, 0x2e, 0x63, 0x68, 0x61, 0x74, 0x2e, 0x63, 0x6f, 0x6d, 0x6d, 0x6f, 0x6e,
     0x73, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x63, 0x6f, 0x6c, 0x2e, 0x4d, 0x65, 0x73, 0x73, 0x61,
     0x67, 0x65, 0x2e, 0x54, 0x79, 0x70, 0x65, 0x12, 0x0c, 0x0a, 0x04, 0x74, 0x65, 0x78, 0x74, 0x18,
     0x03, 0x20, 0x03, 0x28, 0x09, 0x12, 0x0e, 0x0a, 0x06, 0x61, 0x75, 0x74, 0x68, 0x6f, 0x72, 0x18,
-    0x04, 0x20, 0x01, 0x28, 0x09, 0x22, 0x20, 0x0a, 0x04, 0x54, 0x79, 0x70, 0x65, 0x12, 0x0b, 0x0a,
-    0x07, 0x43, 0x4f, 0x4d, 0x4d, 0x41, 0x4e, 0x44, 0x10, 0x01, 0x12, 0x0b, 0x0a, 0x07, 0x4d, 0x45,
-    0x53, 0x53, 0x41, 0x47, 0x45, 0x10, 0x02, 0x4a, 0xe5, 0x04, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00,
-    0x0b, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x00, 0x08, 0x26, 0x0a, 0x0a, 0x0a, 0x02,
-    0x04, 0x00, 0x12, 0x04, 0x02, 0x00, 0x0b, 0x01, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12,
-    0x03, 0x02, 0x08, 0x0f, 0x0a, 0x0c, 0x0a, 0x04, 0x04, 0x00, 0x04, 0x00, 0x12, 0x04, 0x03, 0x04,
-    0x06, 0x05, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x04, 0x00, 0x01, 0x12, 0x03, 0x03, 0x09, 0x0d,
-    0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x04, 0x08, 0x14, 0x0a,
-    0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x04, 0x08, 0x0f, 0x0a,
-    0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x02, 0x12, 0x03, 0x04, 0x12, 0x13, 0x0a,
-    0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x05, 0x08, 0x14, 0x0a, 0x0e,
-    0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x05, 0x08, 0x0f, 0x0a, 0x0e,
-    0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x02, 0x12, 0x03, 0x05, 0x12, 0x13, 0x0a, 0x0b,
-    0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x08, 0x04, 0x1b, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
-    0x00, 0x02, 0x00, 0x04, 0x12, 0x03, 0x08, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
-    0x00, 0x06, 0x12, 0x03, 0x08, 0x0d, 0x11, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01,
-    0x12, 0x03, 0x08, 0x12, 0x16, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03,
-    0x08, 0x19, 0x1a, 0x0a, 0xbf, 0x01, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x09, 0x04,
-    0x1d, 0x22, 0xb1, 0x01, 0x20, 0x72, 0x65, 0x70, 0x65, 0x61, 0x74, 0x65, 0x64, 0x2c, 0x20, 0xd1,
-    0x87, 0xd1, 0x82, 0xd0, 0xbe, 0xd0, 0xb1, 0xd1, 0x8b, 0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd1, 0x80,
-    0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x80, 0x20, 0xd0, 0xbc, 0xd0, 0xbe, 0xd0, 0xb3, 0x20, 0xd1, 0x81,
-    0xd0, 0xbb, 0xd0, 0xb0, 0xd1, 0x82, 0xd1, 0x8c, 0x20, 0xd1, 0x81, 0xd0, 0xbf, 0xd0, 0xb8, 0xd1,
-    0x81, 0xd0, 0xbe, 0xd0, 0xba, 0x20, 0xd1, 0x81, 0xd1, 0x82, 0xd1, 0x80, 0xd0, 0xbe, 0xd0, 0xba,
-    0x20, 0xd0, 0xb2, 0x20, 0xd0, 0xbe, 0xd1, 0x82, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x82, 0x20, 0xd0,
-    0xbd, 0xd0, 0xb0, 0x20, 0xd0, 0xba, 0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb0, 0xd0, 0xbd, 0xd0, 0xb4,
-    0xd1, 0x83, 0x2c, 0x20, 0xd0, 0xb0, 0x20, 0xd0, 0xba, 0xd0, 0xbb, 0xd0, 0xb8, 0xd0, 0xb5, 0xd0,
-    0xbd, 0xd1, 0x82, 0x20, 0xd1, 0x81, 0xd0, 0xb0, 0xd0, 0xbc, 0x20, 0xd0, 0xbf, 0xd1, 0x80, 0xd0,
-    0xbe, 0xd1, 0x81, 0xd1, 0x82, 0xd0, 0xb0, 0xd0, 0xb2, 0xd0, 0xbb, 0xd1, 0x8f, 0xd0, 0xbb, 0x20,
-    0xd1, 0x81, 0xd0, 0xb5, 0xd0, 0xbf, 0xd0, 0xb0, 0xd1, 0x80, 0xd0, 0xb0, 0xd1, 0x82, 0xd0, 0xbe,
-    0xd1, 0x80, 0xd1, 0x8b, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x04, 0x12, 0x03,
-    0x09, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x05, 0x12, 0x03, 0x09, 0x0d,
-    0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x09, 0x14, 0x18, 0x0a,
-    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x03, 0x12, 0x03, 0x09, 0x1b, 0x1c, 0x0a, 0x48, 0x0a,
-    0x04, 0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x0a, 0x04, 0x1f, 0x22, 0x3b, 0x20, 0xd0, 0xbd, 0xd0,
-    0xb5, 0x20, 0xd1, 0x88, 0xd0, 0xbb, 0xd0, 0xb5, 0xd0, 0xbc, 0x20, 0xd0, 0xb0, 0xd0, 0xb2, 0xd1,
-    0x82, 0xd0, 0xbe, 0xd1, 0x80, 0xd0, 0xb0, 0x20, 0xd0, 0xb2, 0x20, 0xd1, 0x81, 0xd0, 0xbb, 0xd1,
-    0x83, 0xd1, 0x87, 0xd0, 0xb0, 0xd0, 0xb5, 0x20, 0xd0, 0xba, 0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb0,
-    0xd0, 0xbd, 0xd0, 0xb4, 0xd1, 0x8b, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x04,
-    0x12, 0x03, 0x0a, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x05, 0x12, 0x03,
-    0x0a, 0x0d, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x0a, 0x14,
-    0x1a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x0a, 0x1d, 0x1e,
+    0x04, 0x20, 0x01, 0x28, 0x09, 0x12, 0x0c, 0x0a, 0x04, 0x72, 0x6f, 0x6f, 0x6d, 0x18, 0x05, 0x20,
+    0x01, 0x28, 0x09, 0x12, 0x11, 0x0a, 0x09, 0x72, 0x65, 0x63, 0x69, 0x70, 0x69, 0x65, 0x6e, 0x74,
+    0x18, 0x06, 0x20, 0x01, 0x28, 0x09, 0x12, 0x10, 0x0a, 0x08, 0x70, 0x61, 0x73, 0x73, 0x77, 0x6f,
+    0x72, 0x64, 0x18, 0x07, 0x20, 0x01, 0x28, 0x09, 0x12, 0x0a, 0x0a, 0x02, 0x69, 0x64, 0x18, 0x08,
+    0x20, 0x01, 0x28, 0x04, 0x12, 0x0d, 0x0a, 0x05, 0x73, 0x69, 0x6e, 0x63, 0x65, 0x18, 0x09, 0x20,
+    0x01, 0x28, 0x04, 0x12, 0x0d, 0x0a, 0x05, 0x6c, 0x69, 0x6d, 0x69, 0x74, 0x18, 0x0a, 0x20, 0x01,
+    0x28, 0x0d, 0x12, 0x11, 0x0a, 0x09, 0x74, 0x69, 0x6d, 0x65, 0x73, 0x74, 0x61, 0x6d, 0x70, 0x18,
+    0x0b, 0x20, 0x01, 0x28, 0x03, 0x22, 0x83, 0x01, 0x0a, 0x04, 0x54, 0x79, 0x70, 0x65, 0x12, 0x0b,
+    0x0a, 0x07, 0x43, 0x4f, 0x4d, 0x4d, 0x41, 0x4e, 0x44, 0x10, 0x01, 0x12, 0x0b, 0x0a, 0x07, 0x4d,
+    0x45, 0x53, 0x53, 0x41, 0x47, 0x45, 0x10, 0x02, 0x12, 0x08, 0x0a, 0x04, 0x4a, 0x4f, 0x49, 0x4e,
+    0x10, 0x03, 0x12, 0x09, 0x0a, 0x05, 0x4c, 0x45, 0x41, 0x56, 0x45, 0x10, 0x04, 0x12, 0x09, 0x0a,
+    0x05, 0x52, 0x4f, 0x4f, 0x4d, 0x53, 0x10, 0x05, 0x12, 0x09, 0x0a, 0x05, 0x4c, 0x4f, 0x47, 0x49,
+    0x4e, 0x10, 0x06, 0x12, 0x07, 0x0a, 0x03, 0x57, 0x48, 0x4f, 0x10, 0x07, 0x12, 0x0b, 0x0a, 0x07,
+    0x48, 0x49, 0x53, 0x54, 0x4f, 0x52, 0x59, 0x10, 0x08, 0x12, 0x08, 0x0a, 0x04, 0x50, 0x49, 0x4e,
+    0x47, 0x10, 0x09, 0x12, 0x08, 0x0a, 0x04, 0x50, 0x4f, 0x4e, 0x47, 0x10, 0x0a, 0x12, 0x0c, 0x0a,
+    0x08, 0x52, 0x45, 0x4a, 0x45, 0x43, 0x54, 0x45, 0x44, 0x10, 0x0b, 0x4a, 0x8b, 0x13, 0x0a, 0x06,
+    0x12, 0x04, 0x00, 0x00, 0x1b, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x00, 0x08, 0x26,
+    0x0a, 0x0a, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x02, 0x00, 0x1b, 0x01, 0x0a, 0x0a, 0x0a, 0x03,
+    0x04, 0x00, 0x01, 0x12, 0x03, 0x02, 0x08, 0x0f, 0x0a, 0x0c, 0x0a, 0x04, 0x04, 0x00, 0x04, 0x00,
+    0x12, 0x04, 0x03, 0x04, 0x0f, 0x05, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x04, 0x00, 0x01, 0x12,
+    0x03, 0x03, 0x09, 0x0d, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03,
+    0x04, 0x08, 0x14, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03,
+    0x04, 0x08, 0x0f, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x02, 0x12, 0x03,
+    0x04, 0x12, 0x13, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x05,
+    0x08, 0x14, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x05,
+    0x08, 0x0f, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x01, 0x02, 0x12, 0x03, 0x05,
+    0x12, 0x13, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x06, 0x08,
+    0x11, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x06, 0x08,
+    0x0c, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x02, 0x02, 0x12, 0x03, 0x06, 0x0f,
+    0x10, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x03, 0x12, 0x03, 0x07, 0x08, 0x12,
+    0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x03, 0x01, 0x12, 0x03, 0x07, 0x08, 0x0d,
+    0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x03, 0x02, 0x12, 0x03, 0x07, 0x10, 0x11,
+    0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x04, 0x12, 0x03, 0x08, 0x08, 0x12, 0x0a,
+    0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x04, 0x01, 0x12, 0x03, 0x08, 0x08, 0x0d, 0x0a,
+    0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x04, 0x02, 0x12, 0x03, 0x08, 0x10, 0x11, 0x0a,
+    0x54, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x05, 0x12, 0x03, 0x09, 0x08, 0x12, 0x22, 0x45,
+    0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x80, 0x20, 0xd0, 0xbe,
+    0xd1, 0x82, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x87, 0xd0, 0xb0, 0xd0, 0xb5, 0xd1, 0x82, 0x20, 0x4c,
+    0x4f, 0x47, 0x49, 0x4e, 0x2c, 0x20, 0xd0, 0xb5, 0xd1, 0x81, 0xd0, 0xbb, 0xd0, 0xb8, 0x20, 0xd0,
+    0xb2, 0xd1, 0x85, 0xd0, 0xbe, 0xd0, 0xb4, 0x20, 0xd1, 0x83, 0xd0, 0xb4, 0xd0, 0xb0, 0xd0, 0xbb,
+    0xd1, 0x81, 0xd1, 0x8f, 0x0a, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x05, 0x01,
+    0x12, 0x03, 0x09, 0x08, 0x0d, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x05, 0x02,
+    0x12, 0x03, 0x09, 0x10, 0x11, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x06, 0x12,
+    0x03, 0x0a, 0x08, 0x10, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x06, 0x01, 0x12,
+    0x03, 0x0a, 0x08, 0x0b, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x06, 0x02, 0x12,
+    0x03, 0x0a, 0x0e, 0x0f, 0x0a, 0x0d, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x07, 0x12, 0x03,
+    0x0b, 0x08, 0x14, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x07, 0x01, 0x12, 0x03,
+    0x0b, 0x08, 0x0f, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00, 0x02, 0x07, 0x02, 0x12, 0x03,
+    0x0b, 0x12, 0x13, 0x0a, 0x67, 0x0a, 0x06, 0x04, 0x00, 0x04, 0x00, 0x02, 0x08, 0x12, 0x03, 0x0c,
+    0x08, 0x11, 0x22, 0x58, 0x20, 0xd0, 0xbf, 0xd1, 0x80, 0xd0, 0xbe, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1,
+    0x80, 0xd0, 0xba, 0xd0, 0xb0, 0x2c, 0x20, 0xd1, 0x87, 0xd1, 0x82, 0xd0, 0xbe, 0x20, 0xd1, 0x81,
+    0xd0, 0xbe, 0xd0, 0xb1, 0xd0, 0xb5, 0xd1, 0x81, 0xd0, 0xb5, 0xd0, 0xb4, 0xd0, 0xbd, 0xd0, 0xb8,
+    0xd0, 0xba, 0x20, 0xd0, 0xb6, 0xd0, 0xb8, 0xd0, 0xb2, 0x3b, 0x20, 0xd0, 0xbe, 0xd1, 0x82, 0xd0,
+    0xb2, 0xd0, 0xb5, 0xd1, 0x87, 0xd0, 0xb0, 0xd1, 0x82, 0xd1, 0x8c, 0x20, 0xd0, 0xbd, 0xd1, 0x83,
+    0xd0, 0xb6, 0xd0, 0xbd, 0xd0, 0xbe, 0x20, 0x50, 0x4f, 0x4e, 0x47, 0x0a, 0x0a, 0x0e, 0x0a, 0x07,
+    0x04, 0x00, 0x04, 0x00, 0x02, 0x08, 0x01, 0x12, 0x03, 0x0c, 0x08, 0x0c, 0x0a, 0x0e, 0x0a, 0x07,
+    0x04, 0x00, 0x04, 0x00, 0x02, 0x08, 0x02, 0x12, 0x03, 0x0c, 0x0f, 0x10, 0x0a, 0x0d, 0x0a, 0x06,
+    0x04, 0x00, 0x04, 0x00, 0x02, 0x09, 0x12, 0x03, 0x0d, 0x08, 0x12, 0x0a, 0x0e, 0x0a, 0x07, 0x04,
+    0x00, 0x04, 0x00, 0x02, 0x09, 0x01, 0x12, 0x03, 0x0d, 0x08, 0x0c, 0x0a, 0x0e, 0x0a, 0x07, 0x04,
+    0x00, 0x04, 0x00, 0x02, 0x09, 0x02, 0x12, 0x03, 0x0d, 0x0f, 0x11, 0x0a, 0x8a, 0x01, 0x0a, 0x06,
+    0x04, 0x00, 0x04, 0x00, 0x02, 0x0a, 0x12, 0x03, 0x0e, 0x08, 0x16, 0x22, 0x7b, 0x20, 0xd1, 0x81,
+    0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x80, 0x20, 0xd0, 0xbd, 0xd0, 0xb5, 0x20,
+    0xd0, 0xbf, 0xd1, 0x80, 0xd0, 0xb8, 0xd0, 0xbd, 0xd1, 0x8f, 0xd0, 0xbb, 0x20, 0xd1, 0x81, 0xd0,
+    0xbe, 0xd0, 0xb5, 0xd0, 0xb4, 0xd0, 0xb8, 0xd0, 0xbd, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd0,
+    0xb5, 0x2c, 0x20, 0xd0, 0xb2, 0x20, 0x74, 0x65, 0x78, 0x74, 0x20, 0xd0, 0xbf, 0xd1, 0x80, 0xd0,
+    0xb8, 0xd1, 0x87, 0xd0, 0xb8, 0xd0, 0xbd, 0xd0, 0xb0, 0x3b, 0x20, 0xd0, 0xbc, 0xd0, 0xbe, 0xd0,
+    0xb6, 0xd0, 0xbd, 0xd0, 0xbe, 0x20, 0xd0, 0xbf, 0xd0, 0xbe, 0xd0, 0xbf, 0xd1, 0x80, 0xd0, 0xbe,
+    0xd0, 0xb1, 0xd0, 0xbe, 0xd0, 0xb2, 0xd0, 0xb0, 0xd1, 0x82, 0xd1, 0x8c, 0x20, 0xd0, 0xbf, 0xd0,
+    0xbe, 0xd0, 0xb7, 0xd0, 0xb6, 0xd0, 0xb5, 0x0a, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00,
+    0x02, 0x0a, 0x01, 0x12, 0x03, 0x0e, 0x08, 0x10, 0x0a, 0x0e, 0x0a, 0x07, 0x04, 0x00, 0x04, 0x00,
+    0x02, 0x0a, 0x02, 0x12, 0x03, 0x0e, 0x13, 0x15, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00,
+    0x12, 0x03, 0x11, 0x04, 0x1b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x03,
+    0x11, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x06, 0x12, 0x03, 0x11, 0x0d,
+    0x11, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x11, 0x12, 0x16, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x11, 0x19, 0x1a, 0x0a, 0xbf, 0x01,
+    0x0a, 0x04, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x12, 0x04, 0x1d, 0x22, 0xb1, 0x01, 0x20, 0x72,
+    0x65, 0x70, 0x65, 0x61, 0x74, 0x65, 0x64, 0x2c, 0x20, 0xd1, 0x87, 0xd1, 0x82, 0xd0, 0xbe, 0xd0,
+    0xb1, 0xd1, 0x8b, 0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x80,
+    0x20, 0xd0, 0xbc, 0xd0, 0xbe, 0xd0, 0xb3, 0x20, 0xd1, 0x81, 0xd0, 0xbb, 0xd0, 0xb0, 0xd1, 0x82,
+    0xd1, 0x8c, 0x20, 0xd1, 0x81, 0xd0, 0xbf, 0xd0, 0xb8, 0xd1, 0x81, 0xd0, 0xbe, 0xd0, 0xba, 0x20,
+    0xd1, 0x81, 0xd1, 0x82, 0xd1, 0x80, 0xd0, 0xbe, 0xd0, 0xba, 0x20, 0xd0, 0xb2, 0x20, 0xd0, 0xbe,
+    0xd1, 0x82, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x82, 0x20, 0xd0, 0xbd, 0xd0, 0xb0, 0x20, 0xd0, 0xba,
+    0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb0, 0xd0, 0xbd, 0xd0, 0xb4, 0xd1, 0x83, 0x2c, 0x20, 0xd0, 0xb0,
+    0x20, 0xd0, 0xba, 0xd0, 0xbb, 0xd0, 0xb8, 0xd0, 0xb5, 0xd0, 0xbd, 0xd1, 0x82, 0x20, 0xd1, 0x81,
+    0xd0, 0xb0, 0xd0, 0xbc, 0x20, 0xd0, 0xbf, 0xd1, 0x80, 0xd0, 0xbe, 0xd1, 0x81, 0xd1, 0x82, 0xd0,
+    0xb0, 0xd0, 0xb2, 0xd0, 0xbb, 0xd1, 0x8f, 0xd0, 0xbb, 0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd0, 0xbf,
+    0xd0, 0xb0, 0xd1, 0x80, 0xd0, 0xb0, 0xd1, 0x82, 0xd0, 0xbe, 0xd1, 0x80, 0xd1, 0x8b, 0x0a, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x04, 0x12, 0x03, 0x12, 0x04, 0x0c, 0x0a, 0x0c, 0x0a,
+    0x05, 0x04, 0x00, 0x02, 0x01, 0x05, 0x12, 0x03, 0x12, 0x0d, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
+    0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x12, 0x14, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
+    0x01, 0x03, 0x12, 0x03, 0x12, 0x1b, 0x1c, 0x0a, 0x48, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x02, 0x12,
+    0x03, 0x13, 0x04, 0x1f, 0x22, 0x3b, 0x20, 0xd0, 0xbd, 0xd0, 0xb5, 0x20, 0xd1, 0x88, 0xd0, 0xbb,
+    0xd0, 0xb5, 0xd0, 0xbc, 0x20, 0xd0, 0xb0, 0xd0, 0xb2, 0xd1, 0x82, 0xd0, 0xbe, 0xd1, 0x80, 0xd0,
+    0xb0, 0x20, 0xd0, 0xb2, 0x20, 0xd1, 0x81, 0xd0, 0xbb, 0xd1, 0x83, 0xd1, 0x87, 0xd0, 0xb0, 0xd0,
+    0xb5, 0x20, 0xd0, 0xba, 0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb0, 0xd0, 0xbd, 0xd0, 0xb4, 0xd1, 0x8b,
+    0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x04, 0x12, 0x03, 0x13, 0x04, 0x0c, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x05, 0x12, 0x03, 0x13, 0x0d, 0x13, 0x0a, 0x0c, 0x0a,
+    0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x13, 0x14, 0x1a, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
+    0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x13, 0x1d, 0x1e, 0x0a, 0x77, 0x0a, 0x04, 0x04, 0x00, 0x02,
+    0x03, 0x12, 0x03, 0x14, 0x04, 0x1d, 0x22, 0x6a, 0x20, 0xd0, 0xba, 0xd0, 0xbe, 0xd0, 0xbc, 0xd0,
+    0xbd, 0xd0, 0xb0, 0xd1, 0x82, 0xd0, 0xb0, 0x20, 0xd0, 0xb4, 0xd0, 0xbb, 0xd1, 0x8f, 0x20, 0x4d,
+    0x45, 0x53, 0x53, 0x41, 0x47, 0x45, 0x2c, 0x20, 0x4a, 0x4f, 0x49, 0x4e, 0x20, 0xd0, 0xb8, 0x20,
+    0x4c, 0x45, 0x41, 0x56, 0x45, 0x3b, 0x20, 0xd0, 0xb1, 0xd0, 0xb5, 0xd0, 0xb7, 0x20, 0xd0, 0xba,
+    0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xbd, 0xd0, 0xb0, 0xd1, 0x82, 0xd1, 0x8b, 0x20, 0xd1, 0x81, 0xd0,
+    0xbe, 0xd0, 0xbe, 0xd0, 0xb1, 0xd1, 0x89, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd0, 0xb5, 0x20,
+    0xd0, 0xb2, 0xd0, 0xb8, 0xd0, 0xb4, 0xd1, 0x8f, 0xd1, 0x82, 0x20, 0xd0, 0xb2, 0xd1, 0x81, 0xd0,
+    0xb5, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x04, 0x12, 0x03, 0x14, 0x04, 0x0c,
+    0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x05, 0x12, 0x03, 0x14, 0x0d, 0x13, 0x0a, 0x0c,
+    0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x01, 0x12, 0x03, 0x14, 0x14, 0x18, 0x0a, 0x0c, 0x0a, 0x05,
+    0x04, 0x00, 0x02, 0x03, 0x03, 0x12, 0x03, 0x14, 0x1b, 0x1c, 0x0a, 0x50, 0x0a, 0x04, 0x04, 0x00,
+    0x02, 0x04, 0x12, 0x03, 0x15, 0x04, 0x22, 0x22, 0x43, 0x20, 0xd0, 0xbb, 0xd0, 0xbe, 0xd0, 0xb3,
+    0xd0, 0xb8, 0xd0, 0xbd, 0x20, 0xd0, 0xbf, 0xd0, 0xbe, 0xd0, 0xbb, 0xd1, 0x83, 0xd1, 0x87, 0xd0,
+    0xb0, 0xd1, 0x82, 0xd0, 0xb5, 0xd0, 0xbb, 0xd1, 0x8f, 0x20, 0xd0, 0xbb, 0xd0, 0xb8, 0xd1, 0x87,
+    0xd0, 0xbd, 0xd0, 0xbe, 0xd0, 0xb3, 0xd0, 0xbe, 0x20, 0xd1, 0x81, 0xd0, 0xbe, 0xd0, 0xbe, 0xd0,
+    0xb1, 0xd1, 0x89, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd1, 0x8f, 0x0a, 0x0a, 0x0c, 0x0a, 0x05,
+    0x04, 0x00, 0x02, 0x04, 0x04, 0x12, 0x03, 0x15, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
+    0x02, 0x04, 0x05, 0x12, 0x03, 0x15, 0x0d, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04,
+    0x01, 0x12, 0x03, 0x15, 0x14, 0x1d, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x03, 0x12,
+    0x03, 0x15, 0x20, 0x21, 0x0a, 0x4f, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x05, 0x12, 0x03, 0x16, 0x04,
+    0x21, 0x22, 0x42, 0x20, 0xd1, 0x82, 0xd0, 0xbe, 0xd0, 0xbb, 0xd1, 0x8c, 0xd0, 0xba, 0xd0, 0xbe,
+    0x20, 0xd0, 0xb2, 0x20, 0x4c, 0x4f, 0x47, 0x49, 0x4e, 0x2c, 0x20, 0xd0, 0xbb, 0xd0, 0xbe, 0xd0,
+    0xb3, 0xd0, 0xb8, 0xd0, 0xbd, 0x20, 0xd0, 0xbf, 0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xb5, 0xd0, 0xb4,
+    0xd0, 0xb0, 0xd0, 0xb5, 0xd1, 0x82, 0xd1, 0x81, 0xd1, 0x8f, 0x20, 0xd0, 0xb2, 0x20, 0x61, 0x75,
+    0x74, 0x68, 0x6f, 0x72, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x05, 0x04, 0x12, 0x03,
+    0x16, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x05, 0x05, 0x12, 0x03, 0x16, 0x0d,
+    0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x05, 0x01, 0x12, 0x03, 0x16, 0x14, 0x1c, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x05, 0x03, 0x12, 0x03, 0x16, 0x1f, 0x20, 0x0a, 0x62, 0x0a,
+    0x04, 0x04, 0x00, 0x02, 0x06, 0x12, 0x03, 0x17, 0x04, 0x1b, 0x22, 0x55, 0x20, 0xd1, 0x81, 0xd0,
+    0xba, 0xd0, 0xb2, 0xd0, 0xbe, 0xd0, 0xb7, 0xd0, 0xbd, 0xd0, 0xbe, 0xd0, 0xb9, 0x20, 0xd0, 0xbd,
+    0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb5, 0xd1, 0x80, 0x20, 0xd1, 0x81, 0xd0, 0xbe, 0xd0, 0xbe, 0xd0,
+    0xb1, 0xd1, 0x89, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd1, 0x8f, 0x2c, 0x20, 0xd0, 0xbf, 0xd1,
+    0x80, 0xd0, 0xbe, 0xd1, 0x81, 0xd1, 0x82, 0xd0, 0xb0, 0xd0, 0xb2, 0xd0, 0xbb, 0xd1, 0x8f, 0xd0,
+    0xb5, 0xd1, 0x82, 0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xb2, 0xd0, 0xb5, 0xd1, 0x80,
+    0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x06, 0x04, 0x12, 0x03, 0x17, 0x04, 0x0c, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x06, 0x05, 0x12, 0x03, 0x17, 0x0d, 0x13, 0x0a, 0x0c, 0x0a,
+    0x05, 0x04, 0x00, 0x02, 0x06, 0x01, 0x12, 0x03, 0x17, 0x14, 0x16, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
+    0x00, 0x02, 0x06, 0x03, 0x12, 0x03, 0x17, 0x19, 0x1a, 0x0a, 0x6c, 0x0a, 0x04, 0x04, 0x00, 0x02,
+    0x07, 0x12, 0x03, 0x18, 0x04, 0x1e, 0x22, 0x5f, 0x20, 0xd0, 0xb2, 0x20, 0x4c, 0x4f, 0x47, 0x49,
+    0x4e, 0x20, 0xd0, 0xb8, 0x20, 0x48, 0x49, 0x53, 0x54, 0x4f, 0x52, 0x59, 0x3a, 0x20, 0xd0, 0xbf,
+    0xd1, 0x80, 0xd0, 0xb8, 0xd1, 0x81, 0xd0, 0xbb, 0xd0, 0xb0, 0xd1, 0x82, 0xd1, 0x8c, 0x20, 0xd1,
+    0x81, 0xd0, 0xbe, 0xd0, 0xbe, 0xd0, 0xb1, 0xd1, 0x89, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd1,
+    0x8f, 0x20, 0xd1, 0x81, 0x20, 0xd0, 0xbd, 0xd0, 0xbe, 0xd0, 0xbc, 0xd0, 0xb5, 0xd1, 0x80, 0xd0,
+    0xbe, 0xd0, 0xbc, 0x20, 0xd0, 0xb1, 0xd0, 0xbe, 0xd0, 0xbb, 0xd1, 0x8c, 0xd1, 0x88, 0xd0, 0xb5,
+    0x20, 0x73, 0x69, 0x6e, 0x63, 0x65, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x07, 0x04,
+    0x12, 0x03, 0x18, 0x04, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x07, 0x05, 0x12, 0x03,
+    0x18, 0x0d, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x07, 0x01, 0x12, 0x03, 0x18, 0x14,
+    0x19, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x07, 0x03, 0x12, 0x03, 0x18, 0x1c, 0x1d, 0x0a,
+    0x72, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x08, 0x12, 0x03, 0x19, 0x04, 0x1f, 0x22, 0x65, 0x20, 0xd0,
+    0xb2, 0x20, 0x4c, 0x4f, 0x47, 0x49, 0x4e, 0x20, 0xd0, 0xb8, 0x20, 0x48, 0x49, 0x53, 0x54, 0x4f,
+    0x52, 0x59, 0x3a, 0x20, 0xd0, 0xbf, 0xd1, 0x80, 0xd0, 0xb8, 0xd1, 0x81, 0xd0, 0xbb, 0xd0, 0xb0,
+    0xd1, 0x82, 0xd1, 0x8c, 0x20, 0xd0, 0xbd, 0xd0, 0xb5, 0x20, 0xd0, 0xb1, 0xd0, 0xbe, 0xd0, 0xbb,
+    0xd1, 0x8c, 0xd1, 0x88, 0xd0, 0xb5, 0x20, 0x6c, 0x69, 0x6d, 0x69, 0x74, 0x20, 0xd0, 0xbf, 0xd0,
+    0xbe, 0xd1, 0x81, 0xd0, 0xbb, 0xd0, 0xb5, 0xd0, 0xb4, 0xd0, 0xbd, 0xd0, 0xb8, 0xd1, 0x85, 0x20,
+    0xd1, 0x81, 0xd0, 0xbe, 0xd0, 0xbe, 0xd0, 0xb1, 0xd1, 0x89, 0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8,
+    0xd0, 0xb9, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x08, 0x04, 0x12, 0x03, 0x19, 0x04,
+    0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x08, 0x05, 0x12, 0x03, 0x19, 0x0d, 0x13, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x08, 0x01, 0x12, 0x03, 0x19, 0x14, 0x19, 0x0a, 0x0c, 0x0a,
+    0x05, 0x04, 0x00, 0x02, 0x08, 0x03, 0x12, 0x03, 0x19, 0x1c, 0x1e, 0x0a, 0x74, 0x0a, 0x04, 0x04,
+    0x00, 0x02, 0x09, 0x12, 0x03, 0x1a, 0x04, 0x22, 0x22, 0x67, 0x20, 0xd0, 0xb2, 0xd1, 0x80, 0xd0,
+    0xb5, 0xd0, 0xbc, 0xd1, 0x8f, 0x20, 0xd0, 0xbf, 0xd0, 0xbe, 0xd0, 0xbb, 0xd1, 0x83, 0xd1, 0x87,
+    0xd0, 0xb5, 0xd0, 0xbd, 0xd0, 0xb8, 0xd1, 0x8f, 0x20, 0xd1, 0x81, 0xd0, 0xb5, 0xd1, 0x80, 0xd0,
+    0xb2, 0xd0, 0xb5, 0xd1, 0x80, 0xd0, 0xbe, 0xd0, 0xbc, 0x2c, 0x20, 0xd0, 0xbc, 0xd0, 0xb8, 0xd0,
+    0xbb, 0xd0, 0xbb, 0xd0, 0xb8, 0xd1, 0x81, 0xd0, 0xb5, 0xd0, 0xba, 0xd1, 0x83, 0xd0, 0xbd, 0xd0,
+    0xb4, 0xd1, 0x8b, 0x20, 0xd0, 0xbe, 0xd1, 0x82, 0x20, 0xd0, 0xbd, 0xd0, 0xb0, 0xd1, 0x87, 0xd0,
+    0xb0, 0xd0, 0xbb, 0xd0, 0xb0, 0x20, 0xd1, 0x8d, 0xd0, 0xbf, 0xd0, 0xbe, 0xd1, 0x85, 0xd0, 0xb8,
+    0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x09, 0x04, 0x12, 0x03, 0x1a, 0x04, 0x0c, 0x0a,
+    0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x09, 0x05, 0x12, 0x03, 0x1a, 0x0d, 0x12, 0x0a, 0x0c, 0x0a,
+    0x05, 0x04, 0x00, 0x02, 0x09, 0x01, 0x12, 0x03, 0x1a, 0x13, 0x1c, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
+    0x00, 0x02, 0x09, 0x03, 0x12, 0x03, 0x1a, 0x1f, 0x21,
 ];
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
@@ -1,20 +1,26 @@
-use protobuf::{self, Message};
+use protobuf;
 
-use mio::buf::{ByteBuf, Buf};
+use codec;
 
 mod message;
 
-use self::message::Message_Type;
+pub use self::message::Message_Type;
 
 pub use self::message::Message as Post;
 
 impl Post {
     pub fn command(&self) -> Option<&String> {
         match self.get_field_type() {
-            Message_Type::COMMAND => {
-                Some(&self.get_text()[0])
-            }
-            Message_Type::MESSAGE => None
+            Message_Type::COMMAND => self.get_text().first(),
+            _ => None
+        }
+    }
+
+    pub fn room(&self) -> Option<&str> {
+        if self.has_room() {
+            Some(self.get_room())
+        } else {
+            None
         }
     }
 
@@ -37,15 +43,86 @@ impl Post {
         proto
     }
 
+    pub fn login(login: String, password: Option<String>) -> Post {
+        let mut proto = Post::default();
+        proto.set_author(login);
+        if let Some(password) = password {
+            proto.set_password(password);
+        }
+        proto.set_field_type(Message_Type::LOGIN);
+        proto
+    }
+
+    // The reply to a successful login.
+    pub fn logged_in(login: String) -> Post {
+        let mut proto = Post::from_result(format!("Logged in as {}", login));
+        proto.set_field_type(Message_Type::LOGIN);
+        proto
+    }
+
+    // Why the server refused the connection.
+    pub fn rejected(reason: String) -> Post {
+        let mut proto = Post::from_result(format!("Connection refused: {}", reason));
+        proto.set_field_type(Message_Type::REJECTED);
+        proto
+    }
+
+    pub fn who() -> Post {
+        let mut proto = Post::default();
+        proto.set_field_type(Message_Type::WHO);
+        proto
+    }
+
+    pub fn join_room(room: String) -> Post {
+        let mut proto = Post::default();
+        proto.set_room(room);
+        proto.set_field_type(Message_Type::JOIN);
+        proto
+    }
+
+    pub fn leave_room(room: String) -> Post {
+        let mut proto = Post::default();
+        proto.set_room(room);
+        proto.set_field_type(Message_Type::LEAVE);
+        proto
+    }
+
+    pub fn list_rooms() -> Post {
+        let mut proto = Post::default();
+        proto.set_field_type(Message_Type::ROOMS);
+        proto
+    }
+
+    pub fn history(since: Option<u64>, limit: Option<u32>) -> Post {
+        let mut proto = Post::default();
+        if let Some(since) = since {
+            proto.set_since(since);
+        }
+        if let Some(limit) = limit {
+            proto.set_limit(limit);
+        }
+        proto.set_field_type(Message_Type::HISTORY);
+        proto
+    }
+
+    pub fn ping() -> Post {
+        let mut proto = Post::default();
+        proto.set_field_type(Message_Type::PING);
+        proto
+    }
+
+    pub fn pong() -> Post {
+        let mut proto = Post::default();
+        proto.set_field_type(Message_Type::PONG);
+        proto
+    }
+
     pub fn take(mut self) -> (String, Vec<String>) {
         (self.take_author(), self.take_text().into_vec())
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        let size = self.compute_size();
-        let mut buf = ByteBuf::mut_with_capacity((size + 5) as usize);
-        self.write_length_delimited_to_writer(&mut buf).unwrap();
-        buf.flip().bytes().iter().map(|&i| i).collect()
+        codec::encode(self)
     }
 
 }
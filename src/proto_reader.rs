@@ -1,66 +1,230 @@
-use std::marker::PhantomData;
 use std::io::{self, Read};
 
 use protobuf;
-use protobuf::stream::WithCodedInputStream;
 
+use codec::Codec;
+pub use codec::{FrameTooLarge, is_frame_too_large};
 
+// Reads length delimited messages from a source. `fill` reads whatever is
+// available and `next` then hands out complete messages one by one until it
+// returns `None`.
 pub struct ProtoReader<M: protobuf::MessageStatic> {
-    is_reading_length: bool,
-    len_buffer: Vec<u8>,
-    msg_buffer: Vec<u8>,
-    msg_ptr: usize,
-    m: PhantomData<M>,
+    codec: Codec<M>,
 }
 
 impl<M: protobuf::MessageStatic> ProtoReader<M> {
     pub fn new() -> ProtoReader<M> {
+        ProtoReader::from_codec(Codec::new())
+    }
+
+    pub fn with_max_size(max_size: usize) -> ProtoReader<M> {
+        ProtoReader::from_codec(Codec::with_max_size(max_size))
+    }
+
+    fn from_codec(codec: Codec<M>) -> ProtoReader<M> {
         ProtoReader {
-            is_reading_length: true,
-            len_buffer: Vec::new(),
-            msg_buffer: Vec::new(),
-            msg_ptr: 0,
-            m: PhantomData,
+            codec: codec,
         }
     }
 
+    // Blocks until a whole message is read. End of input is an error.
     pub fn read<R: Read>(&mut self, source: &mut R) -> io::Result<M> {
-        let mut byte = [0u8; 1];
+        match try!(self.read_next(source)) {
+            Some(msg) => Ok(msg),
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed")),
+        }
+    }
+
+    // Blocks until a whole message is read, `None` if the source ends
+    // between messages.
+    pub fn read_next<R: Read>(&mut self, source: &mut R) -> io::Result<Option<M>> {
         loop {
-            if self.is_reading_length {
-                try!(source.read(&mut byte));
-                let byte = byte[0];
-                self.len_buffer.push(byte);
-
-                if byte.leading_zeros() > 0 {
-                    let msg_len = match self.len_buffer.with_coded_input_stream(|is| {
-                        is.read_raw_varint32()
-                    }) {
-                        Ok(n) => n as usize,
-                        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e))
-                    };
-                    self.len_buffer.truncate(0);
-                    self.is_reading_length = false;
-                    if self.msg_buffer.len() > msg_len {
-                        self.msg_buffer.truncate(msg_len)
-                    } else {
-                        self.msg_buffer = vec![0;msg_len]
-                    }
-                    self.msg_ptr = 0;
-                }
-            } else {
-                let n = try!(source.read(&mut self.msg_buffer[self.msg_ptr..]));
-                self.msg_ptr += n;
+            if let Some(msg) = try!(self.next()) {
+                return Ok(Some(msg));
+            }
+            if try!(self.fill(source)) == 0 {
+                return self.eof().map(|_| None);
+            }
+        }
+    }
+
+    // Call when the source is exhausted: fails if it was in the middle of a message.
+    pub fn eof(&self) -> io::Result<()> {
+        self.codec.eof()
+    }
+
+    // Makes a single read from the source, returns the number of bytes read.
+    pub fn fill<R: Read>(&mut self, source: &mut R) -> io::Result<usize> {
+        self.codec.read_from(source)
+    }
+
+    // Bytes received but not yet handed out, once `next` returns `None`
+    // these are the beginning of an incomplete message.
+    pub fn buffered(&self) -> usize {
+        self.codec.buffered()
+    }
+
+    // The next complete message, if it is already buffered.
+    pub fn next(&mut self) -> io::Result<Option<M>> {
+        self.codec.next_frame()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use post::Post;
+    use super::{ProtoReader, is_frame_too_large};
+
+    // Hands out the given pieces, one per read.
+    struct Pieces<'a>(Vec<&'a [u8]>);
+
+    impl<'a> Read for Pieces<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+            let mut piece = self.0.remove(0);
+            let n = try!(piece.read(buf));
+            if !piece.is_empty() {
+                self.0.insert(0, piece);
+            }
+            Ok(n)
+        }
+    }
+
+    // xorshift, so that failures can be reproduced.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        // Cuts the data into non-empty pieces at random places.
+        fn split<'a>(&mut self, mut data: &'a [u8]) -> Vec<&'a [u8]> {
+            let mut pieces = Vec::new();
+            while !data.is_empty() {
+                let n = 1 + self.below(data.len());
+                pieces.push(&data[..n]);
+                data = &data[n..];
+            }
+            pieces
+        }
+    }
+
+    fn posts() -> Vec<Post> {
+        vec![
+            Post::from_result("hello".to_string()),
+            Post::from_text("Alice".to_string(), vec![vec!["x"; 300].concat()]),
+            Post::who(),
+            Post::from_text("Bob".to_string(), vec!["a".to_string(), "b".to_string()]),
+        ]
+    }
+
+    fn encode(posts: &[Post]) -> Vec<u8> {
+        posts.iter().flat_map(|post| post.to_bytes()).collect()
+    }
+
+    fn read_all<R: Read>(source: &mut R) -> io::Result<Vec<Post>> {
+        let mut reader = ProtoReader::new();
+        let mut posts = Vec::new();
+        while let Some(post) = try!(reader.read_next(source)) {
+            posts.push(post);
+        }
+        Ok(posts)
+    }
 
-                if self.msg_ptr == self.msg_buffer.len() {
-                    self.is_reading_length = true;
+    // Messages read before the first error, and the kind of the error.
+    fn outcome<R: Read>(source: &mut R) -> (Vec<Post>, Option<io::ErrorKind>) {
+        let mut reader = ProtoReader::new();
+        let mut posts = Vec::new();
+        loop {
+            match reader.read_next(source) {
+                Ok(Some(post)) => posts.push(post),
+                Ok(None) => return (posts, None),
+                Err(e) => return (posts, Some(e.kind())),
+            }
+        }
+    }
 
-                    return match protobuf::parse_from_bytes::<M>(&self.msg_buffer) {
-                        Ok(m) => Ok(m),
-                        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e))
-                    }
+    #[test]
+    fn split_anywhere() {
+        let expected = posts();
+        let data = encode(&expected);
+        for i in 1..data.len() {
+            let mut source = Pieces(vec![&data[..i], &data[i..]]);
+            assert_eq!(read_all(&mut source).unwrap(), expected);
+        }
+        let mut source = Pieces(data.chunks(1).collect());
+        assert_eq!(read_all(&mut source).unwrap(), expected);
+    }
+
+    #[test]
+    fn random_chunks() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for _ in 0..500 {
+            let expected = (0..rng.below(8)).map(|_| posts()[rng.below(4)].clone())
+                                            .collect::<Vec<_>>();
+            let data = encode(&expected);
+            let mut source = Pieces(rng.split(&data));
+            assert_eq!(read_all(&mut source).unwrap(), expected);
+        }
+    }
+
+    // However a broken stream is cut, the same messages come out of it
+    // before the same error.
+    #[test]
+    fn random_chunks_malformed() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        for _ in 0..500 {
+            let mut data = encode(&posts());
+            match rng.below(3) {
+                0 => {
+                    let end = rng.below(data.len());
+                    data.truncate(end);
                 }
+                1 => data.extend(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]),
+                _ => data.extend(&[0xff, 0xff, 0xff, 0xff, 0x1f]),
             }
+            let expected = outcome(&mut &data[..]);
+            assert_eq!(outcome(&mut Pieces(rng.split(&data))), expected);
+        }
+    }
+
+    #[test]
+    fn varint_too_long() {
+        let data = [0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        let e = read_all(&mut &data[..]).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn length_above_u32() {
+        let data = [0xff, 0xff, 0xff, 0xff, 0x1f];
+        let e = read_all(&mut &data[..]).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(!is_frame_too_large(&e));
+    }
+
+    #[test]
+    fn eof_between_frames() {
+        let data = encode(&posts());
+        assert_eq!(read_all(&mut &data[..]).unwrap(), posts());
+        assert_eq!(read_all(&mut &[][..]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn eof_inside_frame() {
+        let long = posts()[1].to_bytes();
+        // Inside the two byte header and inside the body.
+        for &end in &[1, 10] {
+            let e = read_all(&mut &long[..end]).unwrap_err();
+            assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
         }
     }
 }
@@ -1,39 +1,423 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex, Condvar};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::sync::mpsc;
-use std::process::{Command, Output};
+use std::time::Duration;
+
+use time;
 
 pub trait Sender: Send + 'static {
-    fn send(&self, result: String);
+    fn send(&self, result: Vec<String>);
 }
 
 pub struct Task<S: Sender> {
     pub cmd: String,
+    // Login of the requester, concurrency limits are per user.
+    pub user: String,
     pub reply_to: S,
 }
 
-pub fn start<S: Sender>() -> mpsc::Sender<Task<S>> {
-    let (tx, rx) = mpsc::channel();
+// One `name [timeout=ms] program arg...` entry per line. Each `{}` in the
+// arguments is replaced by the next word the user typed after `/name`, and the
+// user must supply exactly as many words as there are `{}`s.
+pub struct AllowList {
+    commands: HashMap<String, Entry>,
+}
+
+struct Entry {
+    template: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+struct Invocation {
+    args: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+impl AllowList {
+    pub fn empty() -> AllowList {
+        AllowList { commands: HashMap::new() }
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<AllowList> {
+        let file = try!(File::open(path));
+        AllowList::read(BufReader::new(file))
+    }
+
+    fn read<R: BufRead>(source: R) -> io::Result<AllowList> {
+        let mut commands = HashMap::new();
+        for (n, line) in source.lines().enumerate() {
+            let line = try!(line);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+
+            let malformed = || io::Error::new(io::ErrorKind::InvalidData,
+                                              format!("Malformed command file line {}", n + 1));
+            let mut words = line.split_whitespace().map(|w| w.to_string()).collect::<Vec<_>>();
+            let mut timeout_ms = None;
+            if words.len() > 1 && words[1].starts_with("timeout=") {
+                timeout_ms = Some(try!(words[1]["timeout=".len()..].parse().map_err(|_| malformed())));
+                words.remove(1);
+            }
+            if words.len() < 2 {
+                return Err(malformed());
+            }
+            commands.insert(words[0].clone(), Entry {
+                template: words[1..].to_vec(),
+                timeout_ms: timeout_ms,
+            });
+        }
+
+        Ok(AllowList { commands: commands })
+    }
+
+    fn expand(&self, cmd: &str) -> Result<Invocation, String> {
+        let mut args = cmd.split_whitespace();
+        let name = match args.next() {
+            Some(name) => name,
+            None => return Err("Bad command".to_string()),
+        };
+        let entry = match self.commands.get(name) {
+            Some(entry) => entry,
+            None => return Err(format!("Command not permitted: {}", name)),
+        };
+
+        let args = args.collect::<Vec<_>>();
+        let n_holes = entry.template.iter().filter(|&w| w == "{}").count();
+        if args.len() != n_holes {
+            return Err(format!("{} takes {} argument(s)", name, n_holes));
+        }
+        if args.iter().any(|arg| arg.starts_with("-")) {
+            return Err("Arguments must not start with -".to_string());
+        }
+
+        let mut args = args.into_iter();
+        Ok(Invocation {
+            args: entry.template.iter()
+                .map(|w| if w == "{}" { args.next().unwrap().to_string() } else { w.clone() })
+                .collect(),
+            timeout_ms: entry.timeout_ms,
+        })
+    }
+}
+
+pub struct Config {
+    pub commands: AllowList,
+    pub dir: PathBuf,
+    // Used for commands without their own timeout.
+    pub timeout_ms: u64,
+    // Both stdout and stderr are cut to this many bytes.
+    pub max_output: usize,
+    pub n_threads: usize,
+    // Commands waiting for a free thread, beyond that users get "queue full".
+    pub queue_len: usize,
+    // Queued and running commands of a single user.
+    pub max_per_user: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            commands: AllowList::empty(),
+            dir: PathBuf::from("."),
+            timeout_ms: 5000,
+            max_output: 64 * 1024,
+            n_threads: 4,
+            queue_len: 64,
+            max_per_user: 2,
+        }
+    }
+}
+
+pub struct Shell<S: Sender> {
+    tasks: mpsc::SyncSender<Task<S>>,
+    pending: Arc<Mutex<HashMap<String, usize>>>,
+    max_per_user: usize,
+}
+
+impl<S: Sender> Clone for Shell<S> {
+    fn clone(&self) -> Self {
+        Shell {
+            tasks: self.tasks.clone(),
+            pending: self.pending.clone(),
+            max_per_user: self.max_per_user,
+        }
+    }
+}
+
+impl<S: Sender> Shell<S> {
+    // Queues the task, or replies right away if the user or the whole pool
+    // has too many commands in flight.
+    pub fn submit(&self, task: Task<S>) {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            let n = pending.entry(task.user.clone()).or_insert(0);
+            if *n >= self.max_per_user {
+                return task.reply_to.send(vec![
+                    format!("You already have {} commands running", n)]);
+            }
+            *n += 1;
+        }
+
+        match self.tasks.try_send(task) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(task)) => {
+                finished(&self.pending, &task.user);
+                task.reply_to.send(vec!["Shell queue is full, try later".to_string()]);
+            }
+            Err(mpsc::TrySendError::Disconnected(task)) => {
+                error!("Shell pool is gone");
+                finished(&self.pending, &task.user);
+                task.reply_to.send(vec!["Shell is not available".to_string()]);
+            }
+        }
+    }
+}
+
+fn finished(pending: &Mutex<HashMap<String, usize>>, user: &str) {
+    let mut pending = pending.lock().unwrap();
+    let done = match pending.get_mut(user) {
+        Some(n) => {
+            *n -= 1;
+            *n == 0
+        }
+        None => false,
+    };
+    if done {
+        pending.remove(user);
+    }
+}
+
+// The threads of a shell pool. They run until every `Shell` is dropped.
+pub struct Pool {
+    threads: Vec<thread::JoinHandle<()>>,
+    stopping: Arc<AtomicBool>,
+}
+
+impl Pool {
+    // Commands still in the queue are discarded, running ones are waited for.
+    pub fn join(self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        for t in self.threads {
+            if t.join().is_err() {
+                error!("Shell thread panicked");
+            }
+        }
+    }
+}
+
+pub fn start<S: Sender>(config: Config) -> (Shell<S>, Pool) {
+    let (tx, rx) = mpsc::sync_channel(config.queue_len);
+    let rx = Arc::new(Mutex::new(rx));
+    let pending = Arc::new(Mutex::new(HashMap::new()));
+    let stopping = Arc::new(AtomicBool::new(false));
+    let max_per_user = config.max_per_user;
+    let config = Arc::new(config);
+
+    let mut threads = Vec::new();
+    for _ in 0..config.n_threads {
+        let rx = rx.clone();
+        let pending = pending.clone();
+        let stopping = stopping.clone();
+        let config = config.clone();
+        threads.push(thread::spawn(move || {
+            loop {
+                // Bind the result first, so that the lock is released
+                // before the command starts.
+                let task = rx.lock().unwrap().recv();
+                match task {
+                    Err(_) => {
+                        info!("Shell pool stopped");
+                        return;
+                    }
+                    Ok(Task {ref user, ..}) if stopping.load(Ordering::SeqCst) => {
+                        finished(&pending, user);
+                    }
+                    Ok(Task {ref cmd, ref user, ref reply_to}) => {
+                        let result = exec(&config, cmd);
+                        finished(&pending, user);
+                        <S as Sender>::send(reply_to, result);
+                    }
+                }
+            }
+        }));
+    }
+
+    let shell = Shell {
+        tasks: tx,
+        pending: pending,
+        max_per_user: max_per_user,
+    };
+    (shell, Pool { threads: threads, stopping: stopping })
+}
+
+const PATH: &'static str = "/usr/local/bin:/usr/bin:/bin";
+
+pub fn exec(config: &Config, cmd: &str) -> Vec<String> {
+    let Invocation { args, timeout_ms } = match config.commands.expand(cmd) {
+        Ok(invocation) => invocation,
+        Err(reply) => return vec![reply],
+    };
+
+    let child = Command::new(&args[0]).args(&args[1..])
+        .env_clear()
+        .env("PATH", PATH)
+        .current_dir(&config.dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return vec![format!("Command {} failed: {}", cmd, e)],
+    };
+
+    // Read on separate threads so that a chatty child can't fill a pipe
+    // and block forever while we are waiting for it to exit.
+    let closed = Arc::new((Mutex::new(0), Condvar::new()));
+    let stdout = read_capped(child.stdout.take().unwrap(), config.max_output, closed.clone());
+    let stderr = read_capped(child.stderr.take().unwrap(), config.max_output, closed.clone());
+
+    // There is no waiting for a child with a timeout, so wait for it to close
+    // both pipes instead, which it does when it exits. Until then it is not
+    // reaped, and it is safe to kill.
+    let timeout_ms = timeout_ms.unwrap_or(config.timeout_ms);
+    let deadline = time::precise_time_ns() + timeout_ms * 1000000;
+    {
+        let (ref lock, ref cvar) = *closed;
+        let mut n_closed = lock.lock().unwrap();
+        while *n_closed < 2 {
+            let now = time::precise_time_ns();
+            if now >= deadline {
+                child.kill().unwrap_or_else(|e| error!("Failed to kill {}, {}", cmd, e));
+                child.wait().ok();
+                return vec![format!("Command {} timed out after {} ms", cmd, timeout_ms)];
+            }
+            let left = deadline - now;
+            let timeout = Duration::new(left / 1000000000, (left % 1000000000) as u32);
+            n_closed = cvar.wait_timeout(n_closed, timeout).unwrap().0;
+        }
+    }
+    let status = match child.wait() {
+        Ok(status) => status,
+        Err(e) => return vec![format!("Command {} failed: {}", cmd, e)],
+    };
+
+    let mut result = Vec::new();
+    for (name, output) in vec![("stdout", stdout), ("stderr", stderr)] {
+        match output.join() {
+            Ok(Ok(Output { data, truncated })) => {
+                result.extend(String::from_utf8_lossy(&data).lines().map(|l| l.to_string()));
+                if truncated {
+                    result.push(format!("({} truncated to {} bytes)", name, config.max_output));
+                }
+            }
+            _ => result.push(format!("(failed to read {})", name)),
+        }
+    }
+    if !status.success() {
+        result.push(format!("Command {} exited with {}", cmd, status));
+    }
+    result
+}
+
+struct Output {
+    data: Vec<u8>,
+    truncated: bool,
+}
+
+// Counts the pipe in `closed` once it is read to the end.
+fn read_capped<R: Read + Send + 'static>(reader: R, cap: usize, closed: Arc<(Mutex<usize>, Condvar)>)
+                                         -> thread::JoinHandle<io::Result<Output>> {
     thread::spawn(move || {
-       loop {
-           match rx.recv() {
-               Err(e) => {error!("Failed to receive shell message: {:?}", e);},
-               Ok(Task {ref cmd, ref reply_to} ) => {
-                   <S as Sender>::send(reply_to, exec(cmd));
-               },
-           }
-       }
-    });
-    return tx;
-}
-
-pub fn exec(cmd: &str) -> String {
-    let args = cmd.split_whitespace().collect::<Vec<_>>();
-    if args.len() == 0 {
-        return "Bad command".to_string();
-    }
-
-    match Command::new(args[0]).args(&args[1..]).output() {
-        Err(e) => format!("Command {} failed: {}", cmd, e).to_string(),
-        Ok(Output {stdout, .. }) => String::from_utf8_lossy(&stdout).to_string()
+        let output = read_to_close(reader, cap);
+        let (ref lock, ref cvar) = *closed;
+        *lock.lock().unwrap() += 1;
+        cvar.notify_one();
+        output
+    })
+}
+
+fn read_to_close<R: Read>(reader: R, cap: usize) -> io::Result<Output> {
+    let mut data = Vec::new();
+    let mut reader = reader.take(cap as u64);
+    try!(reader.read_to_end(&mut data));
+    // Keep draining the pipe, otherwise the child blocks on write.
+    let mut reader = reader.into_inner();
+    let rest = try!(io::copy(&mut reader, &mut io::sink()));
+    Ok(Output { data: data, truncated: rest > 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllowList, Config, exec};
+
+    fn allow_list(lines: &str) -> AllowList {
+        AllowList::read(lines.as_bytes()).unwrap()
+    }
+
+    fn expand(list: &AllowList, cmd: &str) -> Result<(Vec<String>, Option<u64>), String> {
+        list.expand(cmd).map(|inv| (inv.args, inv.timeout_ms))
+    }
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let list = allow_list("# comment\n\n  now date  \nbuild timeout=60000 make all\n");
+        assert_eq!(expand(&list, "now"), Ok((args(&["date"]), None)));
+        assert_eq!(expand(&list, "build"), Ok((args(&["make", "all"]), Some(60000))));
+    }
+
+    #[test]
+    fn malformed() {
+        for lines in &["date\n", "ls timeout=5\n", "ls timeout=soon ls\n"] {
+            assert!(AllowList::read(lines.as_bytes()).is_err(), "accepted {:?}", lines);
+        }
+    }
+
+    #[test]
+    fn holes() {
+        let list = allow_list("ls ls {}\ndiff diff -u {} {}\nnow date\n");
+        assert_eq!(expand(&list, "ls src"), Ok((args(&["ls", "src"]), None)));
+        assert_eq!(expand(&list, "diff a  b"), Ok((args(&["diff", "-u", "a", "b"]), None)));
+        assert_eq!(expand(&list, "ls"), Err("ls takes 1 argument(s)".to_string()));
+        assert_eq!(expand(&list, "diff a"), Err("diff takes 2 argument(s)".to_string()));
+        assert_eq!(expand(&list, "now please"), Err("now takes 0 argument(s)".to_string()));
+    }
+
+    #[test]
+    fn rejected() {
+        let list = allow_list("ls ls {}\n");
+        assert_eq!(expand(&list, "ls -la"), Err("Arguments must not start with -".to_string()));
+        assert_eq!(expand(&list, "rm x"), Err("Command not permitted: rm".to_string()));
+        assert_eq!(expand(&list, "   "), Err("Bad command".to_string()));
+    }
+
+    fn config(lines: &str) -> Config {
+        Config { commands: allow_list(lines), ..Config::default() }
+    }
+
+    #[test]
+    fn exec_output() {
+        let config = config("hello echo hello {}\nfail false\n");
+        assert_eq!(exec(&config, "hello world"), vec!["hello world".to_string()]);
+        let result = exec(&config, "fail");
+        assert_eq!(result.len(), 1);
+        assert!(result[0].starts_with("Command fail exited with"), "{:?}", result);
+    }
+
+    #[test]
+    fn exec_timeout() {
+        let config = config("nap timeout=100 sleep 10\n");
+        assert_eq!(exec(&config, "nap"), vec!["Command nap timed out after 100 ms".to_string()]);
     }
 }